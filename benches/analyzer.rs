@@ -0,0 +1,68 @@
+//! Analyzer benchmarks over synthetic project trees.
+//!
+//! Run with `cargo bench`. Compares the single-threaded walk against the
+//! default parallel walk on trees of increasing size, and against a parallel
+//! walk that stops once the project's technology is found.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use gitignore_gen::analyzer::{self, AnalyzeOptions};
+
+/// Build a tree `width` directories wide and `depth` levels deep with `files` files per level.
+fn synthetic_tree(width: usize, depth: usize, files: usize) -> TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    populate(tmp.path(), width, depth, files);
+    fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+    tmp
+}
+
+fn populate(dir: &Path, width: usize, depth: usize, files: usize) {
+    for i in 0..files {
+        // Files that match no rule, as in most real trees
+        fs::write(dir.join(format!("data{}.txt", i)), "").unwrap();
+    }
+    if depth == 0 {
+        return;
+    }
+    for i in 0..width {
+        let sub = dir.join(format!("pkg{}", i));
+        fs::create_dir(&sub).unwrap();
+        populate(&sub, width, depth - 1, files);
+    }
+}
+
+fn bench_walk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analyze_project");
+    group.sample_size(10);
+
+    for (width, depth, files) in [(4, 3, 20), (6, 4, 20), (8, 4, 40)] {
+        let tree = synthetic_tree(width, depth, files);
+        let label = format!("{}x{}x{}", width, depth, files);
+
+        for (name, threads) in [("sequential", 1), ("parallel", 0)] {
+            let options = AnalyzeOptions { max_depth: depth + 1, threads, ..Default::default() };
+            group.bench_with_input(BenchmarkId::new(name, &label), &options, |b, options| {
+                b.iter(|| analyzer::analyze_project(tree.path(), options))
+            });
+        }
+
+        let options = AnalyzeOptions {
+            max_depth: depth + 1,
+            find_secrets: false,
+            find_artifacts: false,
+            stop_when_found: vec!["Rust".to_string()],
+            ..Default::default()
+        };
+        group.bench_with_input(BenchmarkId::new("until_found", &label), &options, |b, options| {
+            b.iter(|| analyzer::analyze_project(tree.path(), options))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_walk);
+criterion_main!(benches);
@@ -20,7 +20,7 @@ fn main() {
     }
 
     // Sort templates by name for consistent output
-    templates.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+    templates.sort_by_key(|a| a.0.to_lowercase());
 
     // Generate templates.rs
     generate_templates_file(&out_dir, &templates);
@@ -74,7 +74,7 @@ fn generate_templates_file(out_dir: &str, templates: &[(String, String)]) {
     writeln!(file).unwrap();
 
     // Generate static map
-    writeln!(file, "// All available gitignore templates").unwrap();
+    writeln!(file, "/// All available gitignore templates, keyed by lowercase name").unwrap();
     writeln!(
         file,
         "pub static TEMPLATES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {{"
@@ -99,7 +99,7 @@ fn generate_templates_file(out_dir: &str, templates: &[(String, String)]) {
     writeln!(file).unwrap();
 
     // Generate list function
-    writeln!(file, "// List all available template names").unwrap();
+    writeln!(file, "/// List all available template names").unwrap();
     writeln!(file, "pub fn list_templates() -> Vec<&'static str> {{").unwrap();
     writeln!(file, "    vec![").unwrap();
     for (name, _) in templates {
@@ -110,7 +110,7 @@ fn generate_templates_file(out_dir: &str, templates: &[(String, String)]) {
     writeln!(file).unwrap();
 
     // Generate get function
-    writeln!(file, "// Get a template by name (case-insensitive)").unwrap();
+    writeln!(file, "/// Get a template by name (case-insensitive)").unwrap();
     writeln!(file, "pub fn get_template(name: &str) -> Option<&'static str> {{").unwrap();
     writeln!(file, "    let key = name.to_lowercase().replace('/', \"_\");").unwrap();
     writeln!(file, "    TEMPLATES.get(key.as_str()).copied()").unwrap();
@@ -3,19 +3,235 @@
 //! Scans project directories to detect technologies, frameworks, and tools
 //! based on configuration files, extensions, and directory structures.
 
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use once_cell::sync::Lazy;
 use std::collections::HashSet;
-use std::path::Path;
-use walkdir::WalkDir;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default maximum directory depth scanned by the analyzer.
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Default size from which a binary file is reported as large (10 MiB).
+pub const DEFAULT_LARGE_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Errors returned by [`GlobList::new`], [`AnalyzeOptions::new`] and [`os_templates`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// An include or exclude pattern is not a valid glob
+    InvalidGlob {
+        /// Pattern as given
+        pattern: String,
+        /// Reason reported by the glob parser
+        reason: String,
+    },
+    /// Not one of the operating systems with a `Global/*` template
+    UnknownOs(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidGlob { pattern, reason } => {
+                write!(f, "Invalid glob '{}': {}", pattern, reason)
+            }
+            Error::UnknownOs(name) => {
+                write!(f, "Unknown operating system '{}'. Expected: macos, linux, windows", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Options controlling how far and where the analyzer walks.
+#[derive(Debug, Clone)]
+pub struct AnalyzeOptions {
+    /// Maximum directory depth to scan (relative to the scanned root)
+    pub max_depth: usize,
+    /// Skip paths ignored by `.gitignore`, `.ignore` and `.git/info/exclude`
+    pub respect_ignore: bool,
+    /// Number of walker threads (0 picks a value based on available CPUs)
+    pub threads: usize,
+    /// Stop after examining this many files and directories
+    pub max_entries: Option<usize>,
+    /// Stop once the scan has been running for this long
+    pub time_budget: Option<Duration>,
+    /// Collect secret and local environment files
+    pub find_secrets: bool,
+    /// Collect large binaries, archives, media and generated files
+    pub find_artifacts: bool,
+    /// Files at least this large (in bytes) are reported as large binaries
+    pub large_file_size: u64,
+    /// Stop once all of these technologies are detected. Only applies when
+    /// secrets and artefacts are not collected, as those need the whole tree.
+    pub stop_when_found: Vec<String>,
+    /// Paths that are never scanned
+    pub exclude: GlobList,
+    /// Paths scanned even if the default skip list would ignore them
+    pub include: GlobList,
+}
+
+impl Default for AnalyzeOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            respect_ignore: false,
+            threads: 0,
+            max_entries: None,
+            time_budget: None,
+            find_secrets: true,
+            find_artifacts: true,
+            large_file_size: DEFAULT_LARGE_FILE_SIZE,
+            stop_when_found: Vec::new(),
+            exclude: GlobList::default(),
+            include: GlobList::default(),
+        }
+    }
+}
+
+impl AnalyzeOptions {
+    /// Build options from a depth and include/exclude glob patterns.
+    pub fn new(max_depth: usize, exclude: &[String], include: &[String]) -> Result<Self, Error> {
+        Ok(Self {
+            max_depth,
+            exclude: GlobList::new(exclude)?,
+            include: GlobList::new(include)?,
+            ..Self::default()
+        })
+    }
+}
+
+/// Compiled glob patterns split into name and relative-path matchers.
+///
+/// Patterns without a `/` match a file or directory name at any depth
+/// (`third_party`, `*.min.js`); patterns with a `/` match the path relative
+/// to the scanned root (`docs/generated`, `packages/*/build`).
+#[derive(Clone)]
+pub struct GlobList {
+    patterns: Vec<String>,
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl fmt::Debug for GlobList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.patterns).finish()
+    }
+}
+
+impl Default for GlobList {
+    fn default() -> Self {
+        Self { patterns: Vec::new(), names: GlobSet::empty(), paths: GlobSet::empty() }
+    }
+}
+
+impl GlobList {
+    /// Compile glob patterns, failing on the first invalid one.
+    pub fn new(patterns: &[String]) -> Result<Self, Error> {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+            let invalid = |e: globset::Error| Error::InvalidGlob {
+                pattern: pattern.clone(),
+                reason: e.kind().to_string(),
+            };
+            if trimmed.contains('/') {
+                let glob =
+                    GlobBuilder::new(trimmed).literal_separator(true).build().map_err(invalid)?;
+                paths.add(glob);
+            } else {
+                names.add(Glob::new(trimmed).map_err(invalid)?);
+            }
+        }
+
+        let invalid = |e: globset::Error| Error::InvalidGlob {
+            pattern: e.glob().unwrap_or_default().to_string(),
+            reason: e.kind().to_string(),
+        };
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            names: names.build().map_err(invalid)?,
+            paths: paths.build().map_err(invalid)?,
+        })
+    }
+
+    fn is_match(&self, name: &str, rel_path: &str) -> bool {
+        self.names.is_match(name) || self.paths.is_match(rel_path)
+    }
+}
+
+/// Result of scanning a project directory.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Detected technologies, sorted by name
+    pub technologies: Vec<String>,
+    /// Number of files and directories examined
+    pub entries_scanned: usize,
+    /// Whether the scan stopped early because the entry or time budget ran out
+    pub truncated: bool,
+    /// Secret and local environment files found, relative to the scanned root
+    pub secrets: Vec<String>,
+    /// Large binaries, archives, media and generated files, sorted by path
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Why a file or directory was reported as an artefact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Binary file above the size threshold
+    LargeBinary,
+    /// Compressed or packed archive
+    Archive,
+    /// Audio, video or design source file
+    Media,
+    /// Build output, minified bundle, generated code or coverage report
+    Generated,
+}
+
+impl ArtifactKind {
+    /// Short human-readable description.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::LargeBinary => "large binary",
+            Self::Archive => "archive",
+            Self::Media => "media",
+            Self::Generated => "generated",
+        }
+    }
+}
+
+/// A file or directory that probably should not be committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    /// Path relative to the scanned root (directories end with `/`)
+    pub path: String,
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+    /// Why it was reported
+    pub kind: ArtifactKind,
+    /// Suggested ignore pattern covering it
+    pub pattern: String,
+}
 
 /// Detection rule defining how to identify a technology.
 struct DetectionRule {
     /// Technology name (must match template name)
     name: &'static str,
-    /// File patterns that indicate this technology
+    /// File glob patterns that indicate this technology (see `RulePatterns`)
     files: &'static [&'static str],
-    /// File extensions that indicate this technology
+    /// File extensions that indicate this technology (without the leading dot,
+    /// may be multi-part like `d.ts`)
     extensions: &'static [&'static str],
-    /// Directory names that indicate this technology
+    /// Match extensions exactly instead of ignoring ASCII case
+    case_sensitive: bool,
+    /// Directory glob patterns that indicate this technology (see `RulePatterns`)
     directories: &'static [&'static str],
 }
 
@@ -27,119 +243,177 @@ const DETECTION_RULES: &[DetectionRule] = &[
         name: "Rust",
         files: &["Cargo.toml", "Cargo.lock"],
         extensions: &["rs"],
+        case_sensitive: false,
         directories: &["target"],
     },
     DetectionRule {
         name: "Go",
         files: &["go.mod", "go.sum"],
         extensions: &["go"],
+        case_sensitive: false,
         directories: &["vendor"],
     },
     DetectionRule {
         name: "Python",
-        files: &["requirements.txt", "setup.py", "pyproject.toml", "Pipfile"],
+        files: &[
+            "requirements.txt",
+            "requirements-*.txt",
+            "requirements/*.txt",
+            "setup.py",
+            "pyproject.toml",
+            "Pipfile",
+        ],
         extensions: &["py", "pyw"],
+        case_sensitive: false,
         directories: &["venv", ".venv", "__pycache__"],
     },
     DetectionRule {
         name: "Node",
         files: &["package.json", "package-lock.json", "yarn.lock", "pnpm-lock.yaml"],
         extensions: &["js", "mjs", "cjs"],
+        case_sensitive: false,
         directories: &["node_modules"],
     },
-    DetectionRule { name: "Java", files: &[], extensions: &["java"], directories: &[] },
-    DetectionRule { name: "Kotlin", files: &[], extensions: &["kt", "kts"], directories: &[] },
+    DetectionRule {
+        name: "Java",
+        files: &[],
+        extensions: &["java"],
+        case_sensitive: false,
+        directories: &[],
+    },
+    DetectionRule {
+        name: "Kotlin",
+        files: &[],
+        extensions: &["kt", "kts"],
+        case_sensitive: false,
+        directories: &[],
+    },
     DetectionRule {
         name: "Swift",
         files: &["Package.swift"],
         extensions: &["swift"],
+        case_sensitive: false,
         directories: &[],
     },
     DetectionRule {
         name: "C",
-        files: &["Makefile", "CMakeLists.txt"],
-        extensions: &["c", "h"],
+        files: &[],
+        extensions: &["c"],
+        // `.C` is a C++ source by Unix convention
+        case_sensitive: true,
         directories: &[],
     },
     DetectionRule {
         name: "C++",
-        files: &["CMakeLists.txt"],
-        extensions: &["cpp", "cxx", "cc", "hpp", "hxx"],
+        files: &[],
+        extensions: &["cpp", "CPP", "cxx", "cc", "c++", "C", "hpp", "hxx"],
+        case_sensitive: true,
         directories: &[],
     },
     DetectionRule {
         name: "Ruby",
         files: &["Gemfile", "Gemfile.lock", "Rakefile"],
         extensions: &["rb"],
+        case_sensitive: false,
         directories: &[],
     },
     DetectionRule {
         name: "Dart",
         files: &["pubspec.yaml", "pubspec.lock"],
         extensions: &["dart"],
+        case_sensitive: false,
         directories: &[".dart_tool"],
     },
     DetectionRule {
         name: "Elixir",
         files: &["mix.exs"],
         extensions: &["ex", "exs"],
+        case_sensitive: false,
         directories: &["_build", "deps"],
     },
     DetectionRule {
         name: "Scala",
         files: &["build.sbt"],
         extensions: &["scala", "sc"],
+        case_sensitive: false,
         directories: &[],
     },
     DetectionRule {
         name: "Haskell",
         files: &["stack.yaml", "cabal.project"],
         extensions: &["hs", "lhs"],
+        case_sensitive: false,
         directories: &[".stack-work"],
     },
-    DetectionRule { name: "Lua", files: &[], extensions: &["lua"], directories: &[] },
+    DetectionRule {
+        name: "Lua",
+        files: &[],
+        extensions: &["lua"],
+        case_sensitive: false,
+        directories: &[],
+    },
+    DetectionRule {
+        name: "Perl",
+        files: &["cpanfile", "Makefile.PL", "Build.PL"],
+        extensions: &["pl", "pm"],
+        case_sensitive: false,
+        directories: &[],
+    },
     DetectionRule {
         name: "R",
-        files: &["DESCRIPTION", ".Rproj"],
-        extensions: &["r", "R", "rmd"],
+        files: &["DESCRIPTION"],
+        extensions: &["r", "rmd", "Rproj"],
+        case_sensitive: false,
         directories: &[],
     },
     DetectionRule {
         name: "Julia",
         files: &["Project.toml", "Manifest.toml"],
         extensions: &["jl"],
+        case_sensitive: false,
         directories: &[],
     },
     DetectionRule {
         name: "Nim",
         files: &[],
         extensions: &["nim", "nims"],
+        case_sensitive: false,
         directories: &["nimcache"],
     },
     DetectionRule {
         name: "Zig",
         files: &["build.zig"],
         extensions: &["zig"],
+        case_sensitive: false,
         directories: &["zig-cache"],
     },
     DetectionRule {
         name: "OCaml",
         files: &["dune", "dune-project"],
         extensions: &["ml", "mli"],
+        case_sensitive: false,
         directories: &["_build"],
     },
     // === Build Tools ===
-    DetectionRule { name: "Maven", files: &["pom.xml"], extensions: &[], directories: &[] },
+    DetectionRule {
+        name: "Maven",
+        files: &["pom.xml"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[],
+    },
     DetectionRule {
         name: "Gradle",
         files: &["build.gradle", "build.gradle.kts", "settings.gradle"],
         extensions: &[],
+        case_sensitive: false,
         directories: &[".gradle"],
     },
     DetectionRule {
         name: "CMake",
         files: &["CMakeLists.txt"],
         extensions: &["cmake"],
+        case_sensitive: false,
         directories: &["CMakeFiles"],
     },
     // === Frameworks ===
@@ -147,39 +421,57 @@ const DETECTION_RULES: &[DetectionRule] = &[
         name: "Rails",
         files: &["config/routes.rb", "bin/rails"],
         extensions: &[],
+        case_sensitive: false,
         directories: &["app/controllers", "app/models"],
     },
     DetectionRule {
         name: "Flutter",
         files: &["pubspec.yaml"],
         extensions: &[],
+        case_sensitive: false,
         directories: &["android", "ios", "lib"],
     },
-    DetectionRule { name: "Angular", files: &["angular.json"], extensions: &[], directories: &[] },
+    DetectionRule {
+        name: "Angular",
+        files: &["angular.json"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[],
+    },
     DetectionRule {
         name: "Laravel",
         files: &["artisan"],
         extensions: &[],
+        case_sensitive: false,
         directories: &["app/Http", "resources/views"],
     },
-    DetectionRule { name: "Django", files: &["manage.py"], extensions: &[], directories: &[] },
+    DetectionRule {
+        name: "Django",
+        files: &["manage.py"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[],
+    },
     // === Game Engines ===
     DetectionRule {
         name: "Unity",
         files: &[],
         extensions: &["unity", "prefab", "asset"],
+        case_sensitive: false,
         directories: &["Assets", "ProjectSettings"],
     },
     DetectionRule {
         name: "UnrealEngine",
         files: &[],
         extensions: &["uproject"],
+        case_sensitive: false,
         directories: &["Content", "Source"],
     },
     DetectionRule {
         name: "Godot",
         files: &["project.godot"],
         extensions: &["gd", "tscn"],
+        case_sensitive: false,
         directories: &[".godot"],
     },
     // === DevOps/Infrastructure ===
@@ -187,57 +479,803 @@ const DETECTION_RULES: &[DetectionRule] = &[
         name: "Terraform",
         files: &[],
         extensions: &["tf", "tfvars"],
+        case_sensitive: false,
         directories: &[".terraform"],
     },
     DetectionRule {
         name: "Ansible",
         files: &["ansible.cfg", "playbook.yml"],
         extensions: &[],
+        case_sensitive: false,
         directories: &["roles", "group_vars"],
     },
-    // === IDEs ===
+    // === Editors & IDEs ===
     DetectionRule {
         name: "VisualStudio",
         files: &[],
         extensions: &["sln", "csproj", "vbproj"],
+        case_sensitive: false,
         directories: &[".vs"],
     },
-    DetectionRule { name: "JetBrains", files: &[], extensions: &[], directories: &[".idea"] },
+    DetectionRule {
+        name: "Global/JetBrains",
+        files: &[],
+        extensions: &["iml", "ipr", "iws"],
+        case_sensitive: false,
+        // Fleet has no template of its own; its settings live next to IntelliJ's
+        directories: &[".idea", ".fleet"],
+    },
+    DetectionRule {
+        name: "Global/VisualStudioCode",
+        files: &[],
+        extensions: &["code-workspace"],
+        case_sensitive: false,
+        directories: &[".vscode"],
+    },
+    DetectionRule {
+        name: "Global/Vim",
+        files: &["[._]*.sw[a-p]", "Session.vim", ".netrwhist"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[],
+    },
+    DetectionRule {
+        name: "Global/Emacs",
+        files: &["#*#", ".#*", ".emacs.desktop", ".dir-locals.el", ".projectile"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[],
+    },
+    DetectionRule {
+        name: "Global/Eclipse",
+        files: &[".project", ".classpath"],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &[".metadata"],
+    },
+    DetectionRule {
+        name: "Global/Xcode",
+        files: &[],
+        extensions: &[],
+        case_sensitive: false,
+        directories: &["*.xcodeproj", "*.xcworkspace", "xcuserdata"],
+    },
+    DetectionRule {
+        name: "Global/SublimeText",
+        files: &[],
+        extensions: &["sublime-project", "sublime-workspace"],
+        case_sensitive: false,
+        directories: &[],
+    },
+];
+
+/// Manifest files that mark the root of a (sub)project in a monorepo.
+const PROJECT_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "go.mod",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "pubspec.yaml",
+    "Gemfile",
+    "composer.json",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "mix.exs",
+    "Package.swift",
+    "build.sbt",
+    "project.godot",
 ];
 
 /// Analyzes a project directory and returns detected technologies.
 ///
 /// Walks the directory tree and matches files/directories against detection rules.
 /// Automatically skips hidden directories, submodules, and build outputs after detecting.
-pub fn analyze_project(path: &Path) -> Vec<String> {
-    use std::cell::RefCell;
+pub fn analyze_project(path: &Path, options: &AnalyzeOptions) -> Analysis {
+    analyze_scoped(path, &[], options)
+}
+
+/// Analyzes a project directory without descending into the given nested project roots.
+///
+/// Used in monorepo mode so that each root only reports its own technologies.
+/// The walk runs on multiple threads and covers the whole tree unless the
+/// configured entry or time budget is exhausted first, or every technology in
+/// `stop_when_found` has been detected.
+pub fn analyze_scoped(path: &Path, nested_roots: &[PathBuf], options: &AnalyzeOptions) -> Analysis {
+    let detected = Mutex::new(HashSet::new());
+    let hints = Mutex::new(HashSet::new());
+    let scanned = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let started = Instant::now();
+    let secrets = Mutex::new(Vec::new());
+    let artifacts = Mutex::new(Vec::new());
+    // A secret hidden by the old ignore file must still end up in the new one,
+    // so with `respect_ignore` secrets are collected by a separate walk
+    let secrets_inline = options.find_secrets && !options.respect_ignore;
+    let wanted: &[String] = match options.find_secrets || options.find_artifacts {
+        true => &[],
+        false => &options.stop_when_found,
+    };
+
+    let mut builder = walker(path, options);
+    builder.threads(options.threads).filter_entry(scope_filter(path, nested_roots, options));
 
-    let detected = RefCell::new(HashSet::new());
-    let max_depth = 3;
+    builder.build_parallel().run(|| {
+        let (detected, hints, secrets, artifacts) = (&detected, &hints, &secrets, &artifacts);
+        let (scanned, truncated) = (&scanned, &truncated);
+        // Rules and hints this thread has already reported, to avoid locking for known matches
+        let mut seen: HashSet<&'static str> = HashSet::new();
+        let mut seen_hints: HashSet<Hint> = HashSet::new();
 
-    let walker = WalkDir::new(path).max_depth(max_depth).into_iter();
+        Box::new(move |entry| {
+            let Ok(entry) = entry else { return WalkState::Continue };
+            let Some(file_type) = entry.file_type() else { return WalkState::Continue };
+
+            let count = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if over_budget(options, count, started) {
+                truncated.store(true, Ordering::Relaxed);
+                return WalkState::Quit;
+            }
 
-    for entry in walker.filter_entry(|e| should_visit(e, &detected)).filter_map(Result::ok) {
-        let name = entry.file_name().to_string_lossy();
-        let is_dir = entry.file_type().is_dir();
+            let name = entry.file_name().to_string_lossy();
+            let is_dir = file_type.is_dir();
 
-        // Get relative path for path-based pattern matching
-        let rel_path = entry
-            .path()
-            .strip_prefix(path)
-            .map(|p| p.to_string_lossy().replace('\\', "/"))
-            .unwrap_or_default();
+            // Get relative path for path-based pattern matching
+            let rel_path = relative_path(path, entry.path());
+
+            // Skipped directories are still matched (`.idea`, `.venv`), just not entered
+            let descend = !is_dir || should_descend(&entry, &name, &rel_path, options);
+
+            // Build outputs identify their technology directly
+            if !descend {
+                if let Some(tech) = build_dir_tech(&name) {
+                    if !seen.contains(tech) && record(tech, &mut seen, detected, wanted) {
+                        return WalkState::Quit;
+                    }
+                    return WalkState::Skip;
+                }
+            }
 
-        for rule in DETECTION_RULES {
-            if matches_rule(rule, &name, &rel_path, is_dir) {
-                detected.borrow_mut().insert(rule.name.to_string());
+            // The scanned root's own name says nothing about its contents: nested
+            // roots named `lib` or `android` are not Flutter projects
+            if entry.depth() > 0 {
+                for rule in matching_rules(&name, &rel_path, is_dir) {
+                    if !seen.contains(rule.name) && record(rule.name, &mut seen, detected, wanted) {
+                        return WalkState::Quit;
+                    }
+                }
             }
+
+            if !is_dir && secrets_inline && secret_pattern(&name).is_some() {
+                secrets.lock().unwrap().push(rel_path.clone());
+            }
+
+            if options.find_artifacts {
+                if let Some(artifact) = classify_artifact(&entry, &name, &rel_path, options) {
+                    artifacts.lock().unwrap().push(artifact);
+                }
+            }
+
+            if !is_dir {
+                for hint in file_hints(&entry, &name) {
+                    if seen_hints.insert(hint) {
+                        hints.lock().unwrap().insert(hint);
+                    }
+                }
+            }
+
+            // Extensionless scripts: fall back to the interpreter in the shebang
+            if !is_dir && !name.contains('.') {
+                if let Some(tech) = sniff_shebang(&entry).filter(|t| !seen.contains(t)) {
+                    if record(tech, &mut seen, detected, wanted) {
+                        return WalkState::Quit;
+                    }
+                }
+            }
+
+            if descend {
+                WalkState::Continue
+            } else {
+                WalkState::Skip
+            }
+        })
+    });
+
+    let mut detected = std::mem::take(&mut *detected.lock().unwrap());
+    resolve_hints(&mut detected, &hints.into_inner().unwrap());
+
+    let mut technologies: Vec<_> = detected.into_iter().collect();
+    technologies.sort();
+
+    let mut secrets = secrets.into_inner().unwrap();
+    if options.find_secrets && !secrets_inline {
+        secrets = find_secrets(path, nested_roots, options, started, &truncated);
+    }
+    secrets.sort();
+
+    let mut artifacts = artifacts.into_inner().unwrap();
+    artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Analysis {
+        technologies,
+        entries_scanned: scanned.into_inner(),
+        truncated: truncated.into_inner(),
+        secrets,
+        artifacts,
+    }
+}
+
+/// Secret files below `path`, including those hidden by existing ignore files.
+///
+/// Shares the budget of the main walk that began at `started`.
+fn find_secrets(
+    path: &Path,
+    nested_roots: &[PathBuf],
+    options: &AnalyzeOptions,
+    started: Instant,
+    truncated: &AtomicBool,
+) -> Vec<String> {
+    let options = AnalyzeOptions { respect_ignore: false, ..options.clone() };
+    let secrets = Mutex::new(Vec::new());
+    let scanned = AtomicUsize::new(0);
+
+    let mut builder = walker(path, &options);
+    builder.threads(options.threads).filter_entry(scope_filter(path, nested_roots, &options));
+
+    builder.build_parallel().run(|| {
+        let (options, secrets, scanned) = (&options, &secrets, &scanned);
+        Box::new(move |entry| {
+            let Ok(entry) = entry else { return WalkState::Continue };
+            let Some(file_type) = entry.file_type() else { return WalkState::Continue };
+
+            let count = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if over_budget(options, count, started) {
+                truncated.store(true, Ordering::Relaxed);
+                return WalkState::Quit;
+            }
+
+            let name = entry.file_name().to_string_lossy();
+            let rel_path = relative_path(path, entry.path());
+            if !file_type.is_dir() {
+                if secret_pattern(&name).is_some() {
+                    secrets.lock().unwrap().push(rel_path);
+                }
+                WalkState::Continue
+            } else if should_descend(&entry, &name, &rel_path, options) {
+                WalkState::Continue
+            } else {
+                WalkState::Skip
+            }
+        })
+    });
+
+    secrets.into_inner().unwrap()
+}
+
+/// Entry filter keeping a walk inside `path` and out of nested roots and excluded paths.
+fn scope_filter(
+    path: &Path,
+    nested_roots: &[PathBuf],
+    options: &AnalyzeOptions,
+) -> impl Fn(&DirEntry) -> bool + Send + Sync + 'static {
+    let root = path.to_path_buf();
+    let nested = nested_roots.to_vec();
+    let options = options.clone();
+    move |e: &DirEntry| !nested.iter().any(|r| r == e.path()) && should_visit(e, &root, &options)
+}
+
+/// Weak evidence that only counts when the detection rules found nothing stronger.
+///
+/// Overlapping signals (a `CMakeLists.txt`, a `.h` header) could mean C, C++ or
+/// both; actual source files always win over these.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Hint {
+    /// A `.h` header, shared by C and C++
+    CHeader,
+    /// A language enabled in a CMake `project()` or `enable_language()` call
+    CMakeLanguage(&'static str),
+}
+
+/// CMake language identifiers and the technology they indicate.
+const CMAKE_LANGUAGES: &[(&str, &str)] = &[("C", "C"), ("CXX", "C++")];
+
+/// CMake files larger than this are not parsed for languages.
+const CMAKE_MAX_FILE_SIZE: u64 = 256 * 1024;
+
+/// Collect weak evidence from a file.
+fn file_hints(entry: &DirEntry, name: &str) -> Vec<Hint> {
+    if has_extension(name, "h", false) {
+        return vec![Hint::CHeader];
+    }
+    if name == "CMakeLists.txt" || has_extension(name, "cmake", false) {
+        let too_large = entry.metadata().map_or(true, |m| m.len() > CMAKE_MAX_FILE_SIZE);
+        if !too_large {
+            if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                return cmake_languages(&content).into_iter().map(Hint::CMakeLanguage).collect();
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Languages explicitly enabled by `project(...)` or `enable_language(...)` calls.
+///
+/// A bare `project(name)` enables C and C++ implicitly in CMake, but that is not
+/// evidence either is used, so only explicitly listed languages are returned.
+fn cmake_languages(content: &str) -> Vec<&'static str> {
+    // ASCII lowercasing keeps byte offsets valid for slicing `content`
+    let lower = content.to_ascii_lowercase();
+    let mut languages = Vec::new();
+
+    for (command, skip) in [("project", 1), ("enable_language", 0)] {
+        let mut rest = lower.as_str();
+        while let Some(start) = rest.find(command) {
+            let offset = lower.len() - rest.len() + start;
+            let name_end = offset + command.len();
+            rest = &lower[name_end..];
+
+            // Whitespace is allowed before the parenthesis: `project (demo)`
+            let after = &lower[name_end..];
+            let gap = after.len() - after.trim_start().len();
+            if !after[gap..].starts_with('(') {
+                continue;
+            }
+            let args_start = name_end + gap + 1;
+            let Some(len) = content[args_start..].find(')') else { break };
+            rest = &lower[args_start + len..];
+
+            // Skip longer identifiers ending in the command name (e.g. `my_project(`)
+            let prev = lower[..offset].chars().next_back();
+            if prev.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+
+            let args = strip_quoted(&content[args_start..args_start + len]);
+            for arg in args.split_whitespace().skip(skip) {
+                if let Some((_, tech)) = CMAKE_LANGUAGES.iter().find(|(id, _)| *id == arg) {
+                    if !languages.contains(tech) {
+                        languages.push(*tech);
+                    }
+                }
+            }
+        }
+    }
+
+    languages
+}
+
+/// Remove double-quoted strings (e.g. a `DESCRIPTION "A C library"`).
+fn strip_quoted(args: &str) -> String {
+    args.split('"').step_by(2).collect::<Vec<_>>().join(" ")
+}
+
+/// Fill in C/C++ from weak evidence when no source files decided them.
+fn resolve_hints(detected: &mut HashSet<String>, hints: &HashSet<Hint>) {
+    if detected.contains("C") || detected.contains("C++") {
+        return;
+    }
+
+    let declared: Vec<_> = hints
+        .iter()
+        .filter_map(|h| match h {
+            Hint::CMakeLanguage(tech) => Some(*tech),
+            Hint::CHeader => None,
+        })
+        .collect();
+
+    if !declared.is_empty() {
+        detected.extend(declared.into_iter().map(String::from));
+    } else if hints.contains(&Hint::CHeader) {
+        detected.insert("C".to_string());
+    }
+}
+
+/// File name patterns for secrets and machine-local environment files.
+pub const SECRET_PATTERNS: &[&str] = &[
+    ".env",
+    ".env.*",
+    "*.pem",
+    "*.key",
+    "*.p12",
+    "*.pfx",
+    "id_rsa",
+    "id_dsa",
+    "id_ecdsa",
+    "id_ed25519",
+    "credentials.json",
+    "*.tfstate",
+    "*.tfstate.*",
+];
+
+/// Environment file templates that are meant to be committed.
+pub const SECRET_EXCEPTIONS: &[&str] =
+    &[".env.example", ".env.sample", ".env.template", ".env.dist"];
+
+static SECRET_GLOBS: Lazy<GlobSet> = Lazy::new(|| {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in SECRET_PATTERNS {
+        builder.add(Glob::new(pattern).expect("Invalid secret pattern"));
+    }
+    builder.build().expect("Failed to compile secret patterns")
+});
+
+/// The first `SECRET_PATTERNS` entry matching a file name, if it is a secret.
+pub fn secret_pattern(name: &str) -> Option<&'static str> {
+    if SECRET_EXCEPTIONS.contains(&name) {
+        return None;
+    }
+    SECRET_GLOBS.matches(name).into_iter().min().map(|i| SECRET_PATTERNS[i])
+}
+
+/// Archive extensions. `jar` is left out on purpose - `gradle-wrapper.jar` is committed.
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "tar", "tar.gz", "tgz", "tar.bz2", "tar.xz", "7z", "rar", "gz", "bz2", "xz", "iso",
+    "dmg",
+];
+
+/// Audio, video and design source extensions. Images are common assets and only
+/// reported when large.
+const MEDIA_EXTENSIONS: &[&str] =
+    &["mp4", "mov", "avi", "mkv", "webm", "mp3", "wav", "flac", "psd", "blend"];
+
+/// Generated files (minified bundles, generated code, coverage reports).
+const GENERATED_FILES: &[&str] = &[
+    "*.min.js",
+    "*.min.css",
+    "*.js.map",
+    "*.css.map",
+    "*.pb.go",
+    "*_pb2.py",
+    "*_pb2_grpc.py",
+    "*.pb.cc",
+    "*.pb.h",
+    "lcov.info",
+    "coverage.xml",
+    ".coverage",
+    "*.gcda",
+    "*.gcno",
+];
+
+/// Generated directories (coverage reports).
+const GENERATED_DIRECTORIES: &[&str] = &["coverage", "htmlcov", ".nyc_output"];
+
+static GENERATED_GLOBS: Lazy<GlobSet> = Lazy::new(|| {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in GENERATED_FILES {
+        builder.add(Glob::new(pattern).expect("Invalid generated file pattern"));
+    }
+    builder.build().expect("Failed to compile generated file patterns")
+});
+
+/// Report a file or directory that probably should not be committed.
+fn classify_artifact(
+    entry: &DirEntry,
+    name: &str,
+    rel_path: &str,
+    options: &AnalyzeOptions,
+) -> Option<Artifact> {
+    if entry.file_type()?.is_dir() {
+        if !GENERATED_DIRECTORIES.contains(&name) {
+            return None;
         }
+        return Some(Artifact {
+            path: format!("{}/", rel_path),
+            size: 0,
+            kind: ArtifactKind::Generated,
+            pattern: format!("{}/", name),
+        });
     }
 
-    let mut result: Vec<_> = detected.into_inner().into_iter().collect();
-    result.sort();
-    result
+    let size = file_size(entry);
+    let (kind, pattern) = if let Some(i) = GENERATED_GLOBS.matches(name).into_iter().min() {
+        (ArtifactKind::Generated, GENERATED_FILES[i].to_string())
+    } else if let Some(ext) = longest_extension(name, ARCHIVE_EXTENSIONS) {
+        (ArtifactKind::Archive, format!("*.{}", ext))
+    } else if let Some(ext) = longest_extension(name, MEDIA_EXTENSIONS) {
+        (ArtifactKind::Media, format!("*.{}", ext))
+    } else if size >= options.large_file_size && is_binary(entry.path()) {
+        (ArtifactKind::LargeBinary, format!("/{}", rel_path))
+    } else {
+        return None;
+    };
+
+    Some(Artifact { path: rel_path.to_string(), size, kind, pattern })
+}
+
+/// Longest matching extension, so `tar.gz` wins over `gz`.
+fn longest_extension(name: &str, extensions: &[&'static str]) -> Option<&'static str> {
+    extensions.iter().copied().filter(|ext| has_extension(name, ext, false)).max_by_key(|e| e.len())
+}
+
+fn file_size(entry: &DirEntry) -> u64 {
+    entry.metadata().map(|m| m.len()).unwrap_or(0)
+}
+
+/// Whether the start of a file contains a NUL byte, like git's own heuristic.
+fn is_binary(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut head = [0u8; 8000];
+    match std::fs::File::open(path).and_then(|mut f| f.read(&mut head)) {
+        Ok(len) => head[..len].contains(&0),
+        Err(_) => false,
+    }
+}
+
+/// Record a detected technology, remembering it per thread to avoid locking again.
+///
+/// Returns whether every `wanted` technology has now been detected.
+fn record(
+    tech: &'static str,
+    seen: &mut HashSet<&'static str>,
+    detected: &Mutex<HashSet<String>>,
+    wanted: &[String],
+) -> bool {
+    seen.insert(tech);
+    let mut detected = detected.lock().unwrap();
+    detected.insert(tech.to_string());
+    !wanted.is_empty() && wanted.iter().all(|t| detected.contains(t))
+}
+
+/// Files larger than this are never sniffed for a shebang (likely binaries).
+const SHEBANG_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Script interpreters (version suffix stripped) and the technology they indicate.
+/// Shells are deliberately absent - there is no template for them.
+const SHEBANG_INTERPRETERS: &[(&str, &str)] = &[
+    ("python", "Python"),
+    ("pypy", "Python"),
+    ("ruby", "Ruby"),
+    ("node", "Node"),
+    ("nodejs", "Node"),
+    ("perl", "Perl"),
+    ("lua", "Lua"),
+    ("luajit", "Lua"),
+    ("julia", "Julia"),
+    ("Rscript", "R"),
+    ("elixir", "Elixir"),
+    ("runhaskell", "Haskell"),
+    ("swift", "Swift"),
+];
+
+/// Map the shebang of a small, executable, extensionless file to a technology.
+fn sniff_shebang(entry: &DirEntry) -> Option<&'static str> {
+    use std::io::Read;
+
+    let metadata = entry.metadata().ok()?;
+    if !metadata.is_file() || metadata.len() > SHEBANG_MAX_FILE_SIZE {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return None;
+        }
+    }
+
+    let mut head = [0u8; 128];
+    let len = std::fs::File::open(entry.path()).and_then(|mut f| f.read(&mut head)).ok()?;
+    let line = head[..len].split(|&b| b == b'\n').next()?;
+    let line = std::str::from_utf8(line).ok()?.strip_prefix("#!")?;
+
+    interpreter_tech(line)
+}
+
+/// Resolve the technology from a shebang line (without the leading `#!`).
+///
+/// Handles both `/usr/bin/python3` and `/usr/bin/env -S node --flags` forms.
+fn interpreter_tech(line: &str) -> Option<&'static str> {
+    let mut args = line.split_whitespace();
+    let mut program = args.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = args.find(|a| !a.starts_with('-') && !a.contains('='))?;
+    }
+
+    let base = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    SHEBANG_INTERPRETERS.iter().find(|(name, _)| *name == base).map(|(_, tech)| *tech)
+}
+
+/// Editor commands (as found in `$VISUAL`/`$EDITOR`) and their templates.
+const EDITOR_COMMANDS: &[(&str, &str)] = &[
+    ("code", "Global/VisualStudioCode"),
+    ("code-insiders", "Global/VisualStudioCode"),
+    ("vi", "Global/Vim"),
+    ("vim", "Global/Vim"),
+    ("nvim", "Global/Vim"),
+    ("gvim", "Global/Vim"),
+    ("emacs", "Global/Emacs"),
+    ("emacsclient", "Global/Emacs"),
+    ("subl", "Global/SublimeText"),
+    ("idea", "Global/JetBrains"),
+    ("fleet", "Global/JetBrains"),
+];
+
+/// Variables set by editors for their integrated terminals, with the value
+/// prefix that identifies them (empty = any value).
+const EDITOR_TERMINAL_VARS: &[(&str, &str, &str)] = &[
+    ("TERM_PROGRAM", "vscode", "Global/VisualStudioCode"),
+    ("TERMINAL_EMULATOR", "JetBrains", "Global/JetBrains"),
+    ("INSIDE_EMACS", "", "Global/Emacs"),
+    ("VIMRUNTIME", "", "Global/Vim"),
+];
+
+/// Detects the editors in use from the current process environment.
+pub fn detect_editors_from_env() -> Vec<String> {
+    editors_in_env(|key| std::env::var(key).ok())
+}
+
+/// Editor templates indicated by the given environment lookup, sorted and deduplicated.
+fn editors_in_env(var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut editors = Vec::new();
+
+    for key in ["VISUAL", "EDITOR"] {
+        let Some(value) = var(key) else { continue };
+        let Some(command) = value.split_whitespace().next() else { continue };
+        let program = command.rsplit(['/', '\\']).next().unwrap_or(command);
+        let program = program.strip_suffix(".exe").unwrap_or(program);
+        if let Some((_, tech)) = EDITOR_COMMANDS.iter().find(|(cmd, _)| *cmd == program) {
+            editors.push(tech.to_string());
+        }
+    }
+
+    for (key, prefix, tech) in EDITOR_TERMINAL_VARS {
+        if var(key).is_some_and(|value| value.starts_with(prefix)) {
+            editors.push(tech.to_string());
+        }
+    }
+
+    editors.sort();
+    editors.dedup();
+    editors
+}
+
+/// Operating system names accepted by `--os` and their `Global/*` templates.
+const OS_TEMPLATES: &[(&str, &str)] = &[
+    ("macos", "Global/macOS"),
+    ("mac", "Global/macOS"),
+    ("darwin", "Global/macOS"),
+    ("linux", "Global/Linux"),
+    ("windows", "Global/Windows"),
+    ("win", "Global/Windows"),
+];
+
+/// Resolves OS names to `Global/*` templates; an empty list means the current platform.
+pub fn os_templates(names: &[String]) -> Result<Vec<String>, Error> {
+    let current = [std::env::consts::OS.to_string()];
+    let names = if names.is_empty() { &current[..] } else { names };
+
+    let mut templates = Vec::new();
+    for name in names {
+        let lower = name.trim().to_lowercase();
+        let (_, template) = OS_TEMPLATES
+            .iter()
+            .find(|(os, _)| *os == lower)
+            .ok_or_else(|| Error::UnknownOs(name.clone()))?;
+        if !templates.iter().any(|t| t == template) {
+            templates.push(template.to_string());
+        }
+    }
+
+    Ok(templates)
+}
+
+/// Project roots found by [`find_project_roots`].
+#[derive(Debug, Default)]
+pub struct ProjectRoots {
+    /// The scanned directory first, then nested roots sorted by path
+    pub roots: Vec<PathBuf>,
+    /// Number of files and directories examined
+    pub entries_scanned: usize,
+    /// Whether the search stopped early because the entry or time budget ran out
+    pub truncated: bool,
+}
+
+/// Finds project roots below `path` (directories containing a known manifest).
+///
+/// The starting directory is always the first entry. Build outputs, dependency
+/// folders, hidden directories, submodules and excluded paths are not searched.
+/// Like [`analyze_scoped`], the walk is parallel and bounded by the configured
+/// depth and entry/time budgets.
+pub fn find_project_roots(path: &Path, options: &AnalyzeOptions) -> ProjectRoots {
+    let found = Mutex::new(Vec::new());
+    let scanned = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let started = Instant::now();
+
+    let filter = {
+        let root = path.to_path_buf();
+        let options = options.clone();
+        move |e: &DirEntry| is_root_candidate(e, &root, &options)
+    };
+
+    let mut builder = walker(path, options);
+    builder.threads(options.threads).filter_entry(filter);
+
+    builder.build_parallel().run(|| {
+        let (found, scanned, truncated) = (&found, &scanned, &truncated);
+        Box::new(move |entry| {
+            let Ok(entry) = entry else { return WalkState::Continue };
+
+            let count = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if over_budget(options, count, started) {
+                truncated.store(true, Ordering::Relaxed);
+                return WalkState::Quit;
+            }
+
+            // Manifests are matched from the directory listing instead of
+            // probing every directory for each of them
+            let is_manifest = || {
+                let name = entry.file_name().to_string_lossy();
+                PROJECT_MANIFESTS.iter().any(|m| name == *m)
+            };
+            let is_file = entry.file_type().is_some_and(|t| !t.is_dir());
+            if entry.depth() > 1 && is_file && is_manifest() {
+                if let Some(dir) = entry.path().parent() {
+                    found.lock().unwrap().push(dir.to_path_buf());
+                }
+            }
+            WalkState::Continue
+        })
+    });
+
+    let mut nested = found.into_inner().unwrap();
+    nested.sort();
+    nested.dedup();
+
+    let mut roots = vec![path.to_path_buf()];
+    roots.extend(nested);
+    ProjectRoots { roots, entries_scanned: scanned.into_inner(), truncated: truncated.into_inner() }
+}
+
+/// Whether the `count`th entry of a walk that began at `started` exceeds the budget.
+fn over_budget(options: &AnalyzeOptions, count: usize, started: Instant) -> bool {
+    options.max_entries.is_some_and(|max| count > max)
+        || options.time_budget.is_some_and(|t| started.elapsed() > t)
+}
+
+/// Directory walker honouring the depth and ignore-file settings in `options`.
+///
+/// Hidden files and other defaults of the `ignore` crate are disabled; the
+/// analyzer applies its own skip list in `should_visit`.
+fn walker(path: &Path, options: &AnalyzeOptions) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(path);
+    builder.standard_filters(false).max_depth(Some(options.max_depth));
+
+    if options.respect_ignore {
+        builder.git_ignore(true).git_exclude(true).ignore(true).parents(true).require_git(false);
+    }
+
+    builder
+}
+
+/// Entry filter for root discovery - never descends into outputs or dependencies.
+fn is_root_candidate(entry: &DirEntry, root: &Path, options: &AnalyzeOptions) -> bool {
+    if entry.depth() == 0 {
+        return true;
+    }
+
+    let name = entry.file_name().to_string_lossy();
+    let rel_path = relative_path(root, entry.path());
+
+    if options.exclude.is_match(&name, &rel_path) {
+        return false;
+    }
+    // Files are kept so their names can be checked against the manifests
+    if !entry.file_type().is_some_and(|t| t.is_dir()) || options.include.is_match(&name, &rel_path)
+    {
+        return true;
+    }
+
+    if name.starts_with('.') || entry.path().join(".git").exists() {
+        return false;
+    }
+
+    let lower = name.to_lowercase();
+    !BUILD_DIR_TECH.iter().any(|(dir, _)| lower == *dir)
+        && !matches!(lower.as_str(), "build" | "dist" | "out" | "vendor")
 }
 
 /// Build output directories that indicate specific technologies
@@ -250,76 +1288,144 @@ const BUILD_DIR_TECH: &[(&str, &str)] = &[
     ("deps", "Elixir"),
 ];
 
-/// Smart directory filter - detects technology from build dirs, then skips them.
-fn should_visit(entry: &walkdir::DirEntry, detected: &std::cell::RefCell<HashSet<String>>) -> bool {
-    // Always visit files
-    if !entry.file_type().is_dir() {
+/// Entry filter - drops excluded paths before they are matched or entered.
+fn should_visit(entry: &DirEntry, root: &Path, options: &AnalyzeOptions) -> bool {
+    // Always visit the scanned root itself
+    if entry.depth() == 0 {
         return true;
     }
 
     let name = entry.file_name().to_string_lossy();
-    let lower = name.to_lowercase();
+    !options.exclude.is_match(&name, &relative_path(root, entry.path()))
+}
+
+/// Technology indicated by a build output directory name.
+fn build_dir_tech(name: &str) -> Option<&'static str> {
+    BUILD_DIR_TECH.iter().find(|(dir, _)| name.eq_ignore_ascii_case(dir)).map(|(_, tech)| *tech)
+}
+
+/// Smart directory filter - decides whether to walk into a directory.
+///
+/// Hidden directories, submodules and build outputs are skipped unless an
+/// include glob matches them.
+fn should_descend(entry: &DirEntry, name: &str, rel_path: &str, options: &AnalyzeOptions) -> bool {
+    // Always walk the scanned root itself
+    if entry.depth() == 0 || options.include.is_match(name, rel_path) {
+        return true;
+    }
 
     // Skip hidden directories (start with .)
-    if name.starts_with('.') && entry.depth() > 0 {
+    if name.starts_with('.') {
         return false;
     }
 
     // Skip submodules
     let git_file = entry.path().join(".git");
-    if git_file.exists() && entry.depth() > 0 {
+    if git_file.exists() {
         return false;
     }
 
-    // Detect technology from build directories, then skip them
-    for (dir, tech) in BUILD_DIR_TECH {
-        if lower == *dir {
-            detected.borrow_mut().insert(tech.to_string());
-            return false; // Skip traversal but we detected!
+    // Skip build outputs, including those that identify a technology
+    let lower = name.to_lowercase();
+    build_dir_tech(&lower).is_none() && !matches!(lower.as_str(), "build" | "dist" | "out")
+}
+
+/// Path of `path` relative to `root`, with forward slashes on every platform.
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default()
+}
+
+/// `files` and `directories` patterns of every rule, compiled once so each entry
+/// is matched against all rules in a single pass.
+///
+/// Patterns follow gitignore conventions: without a `/` they match the entry name
+/// at any depth (`*.csproj`, `requirements-*.txt`); with a `/` they match the end
+/// of the relative path (`config/routes.rb`), and a leading `/` anchors them to
+/// the scanned root (`/bin/rails`). `*` never crosses a `/`; `**` does.
+struct RulePatterns {
+    names: GlobSet,
+    name_rules: Vec<usize>,
+    paths: GlobSet,
+    path_rules: Vec<usize>,
+}
+
+static FILE_PATTERNS: Lazy<RulePatterns> = Lazy::new(|| RulePatterns::new(|r| r.files));
+static DIRECTORY_PATTERNS: Lazy<RulePatterns> = Lazy::new(|| RulePatterns::new(|r| r.directories));
+
+impl RulePatterns {
+    fn new(patterns_of: fn(&DetectionRule) -> &'static [&'static str]) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        let (mut name_rules, mut path_rules) = (Vec::new(), Vec::new());
+
+        for (index, rule) in DETECTION_RULES.iter().enumerate() {
+            for pattern in patterns_of(rule) {
+                let (set, rules, glob) = match pattern.strip_prefix('/') {
+                    Some(anchored) => (&mut paths, &mut path_rules, anchored.to_string()),
+                    None if pattern.contains('/') => {
+                        (&mut paths, &mut path_rules, format!("**/{}", pattern))
+                    }
+                    None => (&mut names, &mut name_rules, pattern.to_string()),
+                };
+                let glob = GlobBuilder::new(&glob)
+                    .literal_separator(true)
+                    .build()
+                    .unwrap_or_else(|e| panic!("Invalid pattern in rule {}: {}", rule.name, e));
+                set.add(glob);
+                rules.push(index);
+            }
         }
-    }
 
-    // Skip other common build outputs
-    if matches!(lower.as_str(), "build" | "dist" | "out") {
-        return false;
+        Self {
+            names: names.build().expect("Failed to compile rule name patterns"),
+            name_rules,
+            paths: paths.build().expect("Failed to compile rule path patterns"),
+            path_rules,
+        }
     }
 
-    true
+    fn matches(
+        &self,
+        name: &str,
+        rel_path: &str,
+    ) -> impl Iterator<Item = &'static DetectionRule> + '_ {
+        let by_name = self.names.matches(name).into_iter().map(|i| self.name_rules[i]);
+        let by_path = self.paths.matches(rel_path).into_iter().map(|i| self.path_rules[i]);
+        by_name.chain(by_path).map(|i| &DETECTION_RULES[i])
+    }
 }
 
-/// Check if a file/directory matches a detection rule.
-/// Supports both base-name matching and path-based patterns (e.g., "config/routes.rb").
-fn matches_rule(rule: &DetectionRule, name: &str, rel_path: &str, is_dir: bool) -> bool {
+/// Rules matched by a file or directory (may contain duplicates).
+fn matching_rules(name: &str, rel_path: &str, is_dir: bool) -> Vec<&'static DetectionRule> {
     if is_dir {
-        // Check directory name
-        if rule.directories.contains(&name) {
-            return true;
-        }
-        // Check if relative path ends with any directory pattern (e.g., "app/controllers")
-        for dir_pattern in rule.directories {
-            if dir_pattern.contains('/') && rel_path.ends_with(dir_pattern) {
-                return true;
-            }
-        }
-        false
+        return DIRECTORY_PATTERNS.matches(name, rel_path).collect();
+    }
+
+    let by_extension = DETECTION_RULES
+        .iter()
+        .filter(|r| r.extensions.iter().any(|ext| has_extension(name, ext, r.case_sensitive)));
+    FILE_PATTERNS.matches(name, rel_path).chain(by_extension).collect()
+}
+
+/// Check whether a file name ends with `.{ext}`, where `ext` may span several
+/// dots (`d.ts`, `tar.gz`).
+///
+/// Names without an extension never match: `go` is not a Go file and `.rs` is
+/// a dotfile, not a Rust source.
+fn has_extension(name: &str, ext: &str, case_sensitive: bool) -> bool {
+    let (name, ext) = (name.as_bytes(), ext.as_bytes());
+    let Some(stem_len) = name.len().checked_sub(ext.len() + 1) else { return false };
+
+    let stem = &name[..stem_len];
+    if name[stem_len] != b'.' || stem.iter().all(|&b| b == b'.') {
+        return false;
+    }
+
+    let suffix = &name[stem_len + 1..];
+    if case_sensitive {
+        suffix == ext
     } else {
-        // Check exact file matches by name
-        if rule.files.contains(&name) {
-            return true;
-        }
-        // Check path-based patterns (e.g., "config/routes.rb")
-        for file_pattern in rule.files {
-            if file_pattern.contains('/') && rel_path.ends_with(file_pattern) {
-                return true;
-            }
-        }
-        // Check extension matches
-        if let Some(ext) = name.rsplit('.').next() {
-            if rule.extensions.contains(&ext) {
-                return true;
-            }
-        }
-        false
+        suffix.eq_ignore_ascii_case(ext)
     }
 }
 
@@ -334,7 +1440,7 @@ mod tests {
         let tmp = tempdir().unwrap();
         fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
 
-        let detected = analyze_project(tmp.path());
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
         assert!(detected.contains(&"Rust".to_string()));
     }
 
@@ -345,7 +1451,7 @@ mod tests {
         fs::write(tmp.path().join("package.json"), "{}").unwrap();
         fs::write(tmp.path().join("go.mod"), "").unwrap();
 
-        let detected = analyze_project(tmp.path());
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
         assert!(detected.contains(&"Rust".to_string()));
         assert!(detected.contains(&"Node".to_string()));
         assert!(detected.contains(&"Go".to_string()));
@@ -356,7 +1462,387 @@ mod tests {
         let tmp = tempdir().unwrap();
         fs::write(tmp.path().join("main.py"), "").unwrap();
 
-        let detected = analyze_project(tmp.path());
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(detected.contains(&"Python".to_string()));
+    }
+
+    #[test]
+    fn test_find_project_roots() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("package.json"), "{}").unwrap();
+        fs::create_dir_all(tmp.path().join("services/api")).unwrap();
+        fs::write(tmp.path().join("services/api/go.mod"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("node_modules/dep")).unwrap();
+        fs::write(tmp.path().join("node_modules/dep/package.json"), "{}").unwrap();
+
+        let roots = find_project_roots(tmp.path(), &AnalyzeOptions::default());
+        assert_eq!(roots.roots, vec![tmp.path().to_path_buf(), tmp.path().join("services/api")]);
+        assert!(!roots.truncated);
+    }
+
+    #[test]
+    fn test_stop_when_found() {
+        let tmp = tempdir().unwrap();
+        for i in 0..20 {
+            let dir = tmp.path().join(format!("pkg{}", i));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("main.go"), "").unwrap();
+            for j in 0..10 {
+                fs::write(dir.join(format!("data{}.txt", j)), "").unwrap();
+            }
+        }
+
+        let options = AnalyzeOptions {
+            threads: 1,
+            find_secrets: false,
+            find_artifacts: false,
+            stop_when_found: vec!["Go".to_string()],
+            ..AnalyzeOptions::default()
+        };
+        let analysis = analyze_project(tmp.path(), &options);
+        assert_eq!(analysis.technologies, vec!["Go"]);
+        assert!(analysis.entries_scanned < 241);
+        assert!(!analysis.truncated);
+
+        // Secrets and artefacts still need the whole tree
+        let options = AnalyzeOptions { find_artifacts: true, ..options };
+        assert_eq!(analyze_project(tmp.path(), &options).entries_scanned, 241);
+    }
+
+    #[test]
+    fn test_find_project_roots_budget() {
+        let tmp = tempdir().unwrap();
+        for i in 0..20 {
+            let dir = tmp.path().join(format!("pkg{}", i));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("Cargo.toml"), "").unwrap();
+        }
+
+        let options = AnalyzeOptions { max_entries: Some(10), ..AnalyzeOptions::default() };
+        let roots = find_project_roots(tmp.path(), &options);
+        assert!(roots.truncated);
+        assert!(roots.roots.len() < 21);
+
+        // Manifests deeper than the scan depth are not searched for
+        let options = AnalyzeOptions { max_depth: 1, ..AnalyzeOptions::default() };
+        assert_eq!(find_project_roots(tmp.path(), &options).roots, vec![tmp.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_analyze_scoped_skips_nested_roots() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("package.json"), "{}").unwrap();
+        fs::create_dir_all(tmp.path().join("services/api")).unwrap();
+        fs::write(tmp.path().join("services/api/Cargo.toml"), "").unwrap();
+
+        let nested = vec![tmp.path().join("services/api")];
+        let detected = analyze_scoped(tmp.path(), &nested, &AnalyzeOptions::default()).technologies;
+        assert!(detected.contains(&"Node".to_string()));
+        assert!(!detected.contains(&"Rust".to_string()));
+    }
+
+    #[test]
+    fn test_depth_and_globs() {
+        let tmp = tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("a/b/c/d")).unwrap();
+        fs::write(tmp.path().join("a/b/c/d/main.go"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("third_party/lib")).unwrap();
+        fs::write(tmp.path().join("third_party/lib/main.rs"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("build")).unwrap();
+        fs::write(tmp.path().join("build/app.py"), "").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(!detected.contains(&"Go".to_string()));
+        assert!(detected.contains(&"Rust".to_string()));
+        assert!(!detected.contains(&"Python".to_string()));
+
+        let options =
+            AnalyzeOptions::new(10, &["third_party".to_string()], &["build".to_string()]).unwrap();
+        let detected = analyze_project(tmp.path(), &options).technologies;
+        assert!(detected.contains(&"Go".to_string()));
+        assert!(!detected.contains(&"Rust".to_string()));
         assert!(detected.contains(&"Python".to_string()));
     }
+
+    #[test]
+    fn test_respect_ignore_files() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join(".gitignore"), "sdk/\n").unwrap();
+        fs::create_dir_all(tmp.path().join("sdk")).unwrap();
+        fs::write(tmp.path().join("sdk/tool.rb"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("gen")).unwrap();
+        fs::write(tmp.path().join("gen/.ignore"), "*.go\n").unwrap();
+        fs::write(tmp.path().join("gen/api.go"), "").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(detected.contains(&"Ruby".to_string()));
+        assert!(detected.contains(&"Go".to_string()));
+
+        let options = AnalyzeOptions { respect_ignore: true, ..AnalyzeOptions::default() };
+        let detected = analyze_project(tmp.path(), &options).technologies;
+        assert!(!detected.contains(&"Ruby".to_string()));
+        assert!(!detected.contains(&"Go".to_string()));
+    }
+
+    #[test]
+    fn test_entry_budget_truncates() {
+        let tmp = tempdir().unwrap();
+        for i in 0..50 {
+            fs::write(tmp.path().join(format!("file{}.txt", i)), "").unwrap();
+        }
+
+        let options = AnalyzeOptions { max_entries: Some(10), ..AnalyzeOptions::default() };
+        let analysis = analyze_project(tmp.path(), &options);
+        assert!(analysis.truncated);
+
+        let analysis = analyze_project(tmp.path(), &AnalyzeOptions::default());
+        assert!(!analysis.truncated);
+        assert_eq!(analysis.entries_scanned, 51);
+    }
+
+    #[test]
+    fn test_interpreter_tech() {
+        assert_eq!(interpreter_tech("/usr/bin/python3"), Some("Python"));
+        assert_eq!(interpreter_tech("/usr/bin/env python3.11"), Some("Python"));
+        assert_eq!(interpreter_tech("/usr/bin/env -S node --no-warnings"), Some("Node"));
+        assert_eq!(interpreter_tech(" /usr/bin/perl -w"), Some("Perl"));
+        assert_eq!(interpreter_tech("/bin/bash"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_by_shebang() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempdir().unwrap();
+        fs::create_dir(tmp.path().join("bin")).unwrap();
+        let script = tmp.path().join("bin/deploy");
+        fs::write(&script, "#!/usr/bin/env ruby\nputs 'hi'\n").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(!detected.contains(&"Ruby".to_string()));
+
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(detected.contains(&"Ruby".to_string()));
+    }
+
+    #[test]
+    fn test_cmake_languages() {
+        assert_eq!(cmake_languages("project(demo LANGUAGES CXX)"), vec!["C++"]);
+        assert_eq!(cmake_languages("PROJECT(demo C CXX)"), vec!["C", "C++"]);
+        assert_eq!(cmake_languages("project(C)\nenable_language(C)"), vec!["C"]);
+        assert_eq!(cmake_languages("project (demo LANGUAGES CXX)"), vec!["C++"]);
+        assert_eq!(cmake_languages("project(demo)\nenable_language\t(C)"), vec!["C"]);
+        assert!(cmake_languages("set(project_languages C)").is_empty());
+        assert!(cmake_languages("project(demo DESCRIPTION \"A C library\")").is_empty());
+        assert!(cmake_languages("project(demo VERSION 1.0)").is_empty());
+        assert!(cmake_languages("my_project(demo C)").is_empty());
+    }
+
+    #[test]
+    fn test_disambiguate_c_cpp_cmake() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("CMakeLists.txt"), "project(demo LANGUAGES CXX)\n").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert_eq!(detected, vec!["C++".to_string(), "CMake".to_string()]);
+
+        // Sources win over declared languages
+        fs::write(tmp.path().join("main.c"), "").unwrap();
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert_eq!(detected, vec!["C".to_string(), "CMake".to_string()]);
+    }
+
+    #[test]
+    fn test_lone_makefile_is_not_c() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("Makefile"), "all:\n").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert!(detected.is_empty());
+
+        fs::write(tmp.path().join("util.h"), "").unwrap();
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert_eq!(detected, vec!["C".to_string()]);
+    }
+
+    #[test]
+    fn test_has_extension() {
+        assert!(has_extension("main.rs", "rs", false));
+        assert!(has_extension("analysis.R", "r", false));
+        assert!(has_extension("types.d.ts", "d.ts", false));
+        assert!(has_extension("backup.TAR.GZ", "tar.gz", false));
+        assert!(!has_extension("r", "r", false));
+        assert!(!has_extension("go", "go", false));
+        assert!(!has_extension(".rs", "rs", false));
+        assert!(!has_extension("index.ts", "d.ts", false));
+        assert!(!has_extension("main.C", "c", true));
+        assert!(has_extension("main.C", "C", true));
+    }
+
+    #[test]
+    fn test_extension_case_opt_out() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("main.C"), "").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        assert_eq!(detected, vec!["C++".to_string()]);
+    }
+
+    #[test]
+    fn test_rule_patterns() {
+        let names = |name, rel_path, is_dir| -> Vec<&str> {
+            matching_rules(name, rel_path, is_dir).iter().map(|r| r.name).collect()
+        };
+
+        assert_eq!(names("requirements-dev.txt", "requirements-dev.txt", false), vec!["Python"]);
+        assert_eq!(names("base.txt", "requirements/base.txt", false), vec!["Python"]);
+        assert!(names("base.txt", "requirements/nested/base.txt", false).is_empty());
+        assert_eq!(names("routes.rb", "api/config/routes.rb", false), vec!["Rails", "Ruby"]);
+        assert!(names("models", "myapp/models", true).is_empty());
+        assert_eq!(names("models", "app/models", true), vec!["Rails"]);
+        assert_eq!(names("demo.Rproj", "demo.Rproj", false), vec!["R"]);
+    }
+
+    #[test]
+    fn test_detect_editors() {
+        let tmp = tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join(".vscode")).unwrap();
+        fs::create_dir_all(tmp.path().join("apps/ios/App.xcodeproj")).unwrap();
+        fs::create_dir_all(tmp.path().join("services/api/.idea")).unwrap();
+        fs::write(tmp.path().join(".main.c.swp"), "").unwrap();
+        fs::write(tmp.path().join(".project"), "").unwrap();
+
+        let detected = analyze_project(tmp.path(), &AnalyzeOptions::default()).technologies;
+        for tech in [
+            "Global/VisualStudioCode",
+            "Global/Xcode",
+            "Global/JetBrains",
+            "Global/Vim",
+            "Global/Eclipse",
+        ] {
+            assert!(detected.contains(&tech.to_string()), "{} not detected", tech);
+        }
+        assert!(!detected.contains(&"C".to_string()));
+    }
+
+    #[test]
+    fn test_editors_in_env() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(editors_in_env(env(&[("EDITOR", "/usr/bin/nvim -u NONE")])), vec!["Global/Vim"]);
+        assert_eq!(
+            editors_in_env(env(&[("VISUAL", "code --wait"), ("TERM_PROGRAM", "vscode")])),
+            vec!["Global/VisualStudioCode"]
+        );
+        assert_eq!(
+            editors_in_env(env(&[("TERMINAL_EMULATOR", "JetBrains-JediTerm")])),
+            vec!["Global/JetBrains"]
+        );
+        assert!(
+            editors_in_env(env(&[("EDITOR", "nano"), ("TERM_PROGRAM", "iTerm.app")])).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_os_templates() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            os_templates(&names(&["macOS", "windows", "darwin"])).unwrap(),
+            vec!["Global/macOS", "Global/Windows"]
+        );
+        assert_eq!(os_templates(&names(&["beos"])), Err(Error::UnknownOs("beos".to_string())));
+
+        if cfg!(target_os = "linux") {
+            assert_eq!(os_templates(&[]).unwrap(), vec!["Global/Linux"]);
+        }
+    }
+
+    #[test]
+    fn test_secret_pattern() {
+        assert_eq!(secret_pattern(".env"), Some(".env"));
+        assert_eq!(secret_pattern(".env.local"), Some(".env.*"));
+        assert_eq!(secret_pattern("server.pem"), Some("*.pem"));
+        assert_eq!(secret_pattern("terraform.tfstate.backup"), Some("*.tfstate.*"));
+        assert_eq!(secret_pattern(".env.example"), None);
+        assert_eq!(secret_pattern("keyboard.rs"), None);
+    }
+
+    #[test]
+    fn test_find_secrets() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join(".env"), "").unwrap();
+        fs::write(tmp.path().join(".env.example"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("deploy")).unwrap();
+        fs::write(tmp.path().join("deploy/id_rsa"), "").unwrap();
+
+        let analysis = analyze_project(tmp.path(), &AnalyzeOptions::default());
+        assert_eq!(analysis.secrets, vec![".env", "deploy/id_rsa"]);
+    }
+
+    #[test]
+    fn test_root_name_is_not_matched() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("lib");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Cargo.toml"), "").unwrap();
+
+        let detected = analyze_project(&root, &AnalyzeOptions::default()).technologies;
+        assert_eq!(detected, vec!["Rust"]);
+    }
+
+    #[test]
+    fn test_find_secrets_hidden_by_ignore_files() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+        fs::write(tmp.path().join(".gitignore"), "server.pem\nsdk/\n").unwrap();
+        fs::write(tmp.path().join("server.pem"), "").unwrap();
+        fs::create_dir_all(tmp.path().join("sdk")).unwrap();
+        fs::write(tmp.path().join("sdk/tool.rb"), "").unwrap();
+
+        let options = AnalyzeOptions { respect_ignore: true, ..AnalyzeOptions::default() };
+        let analysis = analyze_project(tmp.path(), &options);
+        assert_eq!(analysis.secrets, vec!["server.pem"]);
+        // Detection still honours the ignore file
+        assert_eq!(analysis.technologies, vec!["Rust"]);
+    }
+
+    #[test]
+    fn test_find_artifacts() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("release.tar.gz"), "").unwrap();
+        fs::write(tmp.path().join("app.min.js"), "").unwrap();
+        fs::write(tmp.path().join("blob.bin"), [0u8; 64]).unwrap();
+        fs::write(tmp.path().join("notes.txt"), "x".repeat(64)).unwrap();
+        fs::create_dir_all(tmp.path().join("coverage")).unwrap();
+
+        let options = AnalyzeOptions { large_file_size: 32, ..AnalyzeOptions::default() };
+        let analysis = analyze_project(tmp.path(), &options);
+        let found: Vec<_> = analysis
+            .artifacts
+            .iter()
+            .map(|a| (a.path.as_str(), a.kind, a.pattern.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("app.min.js", ArtifactKind::Generated, "*.min.js"),
+                ("blob.bin", ArtifactKind::LargeBinary, "/blob.bin"),
+                ("coverage/", ArtifactKind::Generated, "coverage/"),
+                ("release.tar.gz", ArtifactKind::Archive, "*.tar.gz"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        let error = AnalyzeOptions::new(3, &["[".to_string()], &[]).err().unwrap();
+        assert!(matches!(&error, Error::InvalidGlob { pattern, .. } if pattern == "["));
+        assert!(error.to_string().starts_with("Invalid glob '['"));
+    }
 }
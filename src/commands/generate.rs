@@ -1,21 +1,88 @@
-use crate::analyzer;
-use crate::templates;
+use super::ScanOptions;
+use crate::config::{self, OsSetting};
+use gitignore_gen::analyzer::{self, AnalyzeOptions, Artifact, ArtifactKind};
+use gitignore_gen::generator::{self, Generator, IgnoreKind};
+use gitignore_gen::templates;
+use ignore::gitignore::Gitignore;
 use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Options for the generate command, collected from command-line flags.
+pub struct GenerateOptions {
+    /// Overwrite existing files
+    pub force: bool,
+    /// Print the generated files instead of writing them
+    pub dry_run: bool,
+    /// Explicit technologies (auto-detect when empty)
+    pub technologies: Vec<String>,
+    /// Generate one file per project root
+    pub recursive: bool,
+    /// Scan settings (override config)
+    pub scan: ScanOptions,
+    /// Add templates for the editor found in the environment
+    pub editor_env: bool,
+    /// Operating systems to add templates for (empty = current platform)
+    pub os: Option<Vec<String>>,
+    /// Add suggested patterns for uncovered artefacts to a custom section
+    pub accept_suggestions: bool,
+    /// Kind of ignore file to write
+    pub kind: IgnoreKind,
+}
 
 /// Generate .gitignore file based on detected or specified technologies
-pub fn generate(force: bool, custom: &[String]) -> Result<(), String> {
+pub fn generate(options: &GenerateOptions) -> Result<(), String> {
     let path = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let config = config::load(&path)?;
+
+    let analyze_options = options.scan.analyze_options(&config.analyzer)?;
+
+    // Nothing is written in a dry run, so existing files are no obstacle
+    let force = options.force || options.dry_run;
+    let custom = &options.technologies;
+
+    // Editors and operating systems, added on top of what the scan finds
+    let mut extras = if options.editor_env || config.analyzer.editor_env.unwrap_or(false) {
+        analyzer::detect_editors_from_env()
+    } else {
+        Vec::new()
+    };
+    let os = match (&options.os, config.analyzer.os) {
+        (Some(names), _) => Some(names.clone()),
+        (None, Some(OsSetting::Enabled(true))) => Some(Vec::new()),
+        (None, Some(OsSetting::List(names))) => Some(names),
+        (None, _) => None,
+    };
+    if let Some(names) = os {
+        extras.extend(analyzer::os_templates(&names).map_err(|e| e.to_string())?);
+    }
 
-    // Check if .gitignore exists
-    let gitignore_path = path.join(".gitignore");
+    let accept = options.accept_suggestions || config.analyzer.accept_suggestions.unwrap_or(false);
+
+    let kind = options.kind;
+    if options.recursive {
+        if kind != IgnoreKind::Gitignore {
+            return Err("--recursive only supports .gitignore files".to_string());
+        }
+        let output = Output { force, dry_run: options.dry_run, accept };
+        return generate_recursive(&path, &output, &analyze_options, &extras);
+    }
+
+    // Check if the file exists
+    let file_name = kind.file_name();
+    let gitignore_path = path.join(file_name);
     if gitignore_path.exists() && !force {
-        return Err("A .gitignore file already exists. Use --force to overwrite.".to_string());
+        return Err(format!("A {} file already exists. Use --force to overwrite.", file_name));
     }
 
+    let analysis = analyzer::analyze_project(&path, &analyze_options);
+    warn_if_truncated(&path, &analysis);
+
     // Get technologies: custom if provided, otherwise auto-detect
     let technologies = if custom.is_empty() {
-        let detected = analyzer::analyze_project(&path);
+        let mut detected = analysis.technologies;
+        merge(&mut detected, &extras);
         if detected.is_empty() {
             return Err(
                 "No supported technologies detected. Try specifying manually: gitignore-gen rust"
@@ -38,43 +105,486 @@ pub fn generate(force: bool, custom: &[String]) -> Result<(), String> {
                 "No valid templates specified. Use --list to see available templates.".to_string()
             );
         }
+        merge(&mut valid, &extras);
         valid
     };
 
-    // Build combined gitignore content
-    let content = build_gitignore(&technologies)?;
+    // Build combined gitignore content, making sure secrets are covered
+    let generator =
+        Generator::new().technologies(&technologies).secrets(&analysis.secrets).allow_missing(true);
+    let suggestions = suggest_patterns(&build(&generator)?, &analysis.artifacts);
+    let content = build(&accept_suggestions(generator, accept, &suggestions).kind(kind))?;
+
+    if options.dry_run {
+        print!("{}", content);
+        return Ok(());
+    }
 
     // Write file
     fs::write(&gitignore_path, &content)
-        .map_err(|e| format!("Failed to write .gitignore file: {}", e))?;
+        .map_err(|e| format!("Failed to write {} file: {}", file_name, e))?;
 
-    println!("Generated .gitignore for: {}", technologies.join(", "));
+    println!("Generated {} for: {}", file_name, technologies.join(", "));
+    if !accept {
+        print_suggestions(&suggestions);
+    }
+    if kind == IgnoreKind::Gitignore {
+        warn_tracked_secrets(&path, &analysis.secrets);
+    }
     Ok(())
 }
 
-/// Build combined .gitignore content from technologies
-fn build_gitignore(technologies: &[String]) -> Result<String, String> {
-    let mut content = String::new();
-    content.push_str("# Generated by gitignore-gen\n");
-    content.push_str(&format!("# Technologies: {}\n\n", technologies.join(", ")));
+/// How `generate_recursive` writes its files.
+struct Output {
+    /// Overwrite existing files
+    force: bool,
+    /// Print the files instead of writing them
+    dry_run: bool,
+    /// Add suggested patterns to the top-level file
+    accept: bool,
+}
+
+/// Generate one .gitignore per project root found below `path` (monorepo mode).
+///
+/// Each root only lists technologies whose patterns the ancestor files do not
+/// already apply to it (see [`uncovered`]).
+fn generate_recursive(
+    path: &Path,
+    output: &Output,
+    options: &AnalyzeOptions,
+    extras: &[String],
+) -> Result<(), String> {
+    let accept = output.accept;
+    let found = analyzer::find_project_roots(path, options);
+    if found.truncated {
+        eprintln!(
+            "Warning: Search for project roots stopped after {} entries (budget reached); some subprojects may be missed",
+            found.entries_scanned
+        );
+    }
+    let roots = found.roots;
+
+    // Refuse before writing anything so a partial run never happens
+    if !output.force {
+        let existing: Vec<_> = roots
+            .iter()
+            .map(|r| r.join(".gitignore"))
+            .filter(|p| p.exists())
+            .map(|p| display_path(path, &p))
+            .collect();
+        if !existing.is_empty() {
+            return Err(format!(
+                "A .gitignore file already exists in: {}. Use --force to overwrite.",
+                existing.join(", ")
+            ));
+        }
+    }
+
+    let mut planned: Vec<(PathBuf, Gitignore)> = Vec::new();
+    let mut plan: Vec<(&PathBuf, Vec<String>)> = Vec::new();
+    let mut secrets = Vec::new();
+    let mut artifacts = Vec::new();
+
+    for root in &roots {
+        let nested: Vec<PathBuf> =
+            roots.iter().filter(|r| *r != root && r.starts_with(root)).cloned().collect();
+        let analysis = analyzer::analyze_scoped(root, &nested, options);
+        warn_if_truncated(root, &analysis);
+
+        let mut detected = analysis.technologies;
+        if root == path {
+            merge(&mut detected, extras);
+        }
+
+        // Secrets and suggestions all go in the top-level file, relative to it
+        let prefix = display_path(path, root);
+        let rebase = |p: String| match prefix.as_str() {
+            "." => p,
+            _ => format!("{}/{}", prefix, p),
+        };
+        secrets.extend(analysis.secrets.into_iter().map(rebase));
+        artifacts.extend(analysis.artifacts.into_iter().map(|mut a| {
+            if a.pattern.starts_with('/') {
+                a.pattern = format!("/{}", rebase(a.pattern[1..].to_string()));
+            }
+            a.path = rebase(a.path);
+            a
+        }));
+
+        let technologies = uncovered(root, detected, &mut planned, templates::get_template);
+        plan.push((root, technologies));
+    }
+
+    let mut written = 0;
+    let mut suggestions = Vec::new();
+
+    for (root, technologies) in plan {
+        let label = display_path(path, root);
+        let is_top = root == path;
+        // The top-level file is still needed to cover secrets
+        let needs_secrets = is_top && !secrets.is_empty();
+
+        if technologies.is_empty() && !needs_secrets {
+            if !output.dry_run {
+                println!("Skipped {}: already covered by parent .gitignore", label);
+            }
+            continue;
+        }
 
-    let mut any_added = false;
-    for tech in technologies {
-        if let Some(template) = templates::get_template(tech) {
-            // Skip empty templates
-            if template.trim().is_empty() {
+        let mut generator = Generator::new().technologies(&technologies).allow_missing(true);
+        if is_top {
+            generator = generator.secrets(&secrets);
+            suggestions = match generator.build() {
+                Ok(content) => suggest_patterns(&content, &artifacts),
+                Err(_) => Vec::new(),
+            };
+            generator = accept_suggestions(generator, accept, &suggestions);
+        }
+        let content = match generator.build() {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Warning: Skipping {}: {}", label, e);
                 continue;
             }
-            content.push_str(&format!("### {} ###\n", tech));
-            content.push_str(template);
-            content.push_str("\n\n");
-            any_added = true;
+        };
+
+        written += 1;
+        if output.dry_run {
+            println!("==> {}/.gitignore <==\n{}", label, content);
+            continue;
+        }
+
+        fs::write(root.join(".gitignore"), &content)
+            .map_err(|e| format!("Failed to write {}/.gitignore: {}", label, e))?;
+
+        println!("Generated {}/.gitignore for: {}", label, technologies.join(", "));
+    }
+
+    if written == 0 {
+        return Err("No supported technologies detected in any project root.".to_string());
+    }
+    if output.dry_run {
+        return Ok(());
+    }
+
+    if !accept {
+        print_suggestions(&suggestions);
+    }
+    warn_tracked_secrets(path, &secrets);
+    Ok(())
+}
+
+/// Technologies `root` still needs given the files planned for its ancestors,
+/// recording the patterns of its own file for the roots nested below it.
+///
+/// A technology is inherited only if every pattern of its template, applied at
+/// `root`, is already ignored by an ancestor's file. Anchored patterns such as
+/// `/_build/` or `/vendor/` only apply next to the file that contains them, so
+/// templates using them are repeated in nested roots.
+fn uncovered(
+    root: &Path,
+    detected: Vec<String>,
+    planned: &mut Vec<(PathBuf, Gitignore)>,
+    template: impl Fn(&str) -> Option<&'static str>,
+) -> Vec<String> {
+    let ancestors: Vec<_> = planned
+        .iter()
+        .filter_map(|(ancestor, matcher)| Some((root.strip_prefix(ancestor).ok()?, matcher)))
+        .collect();
+
+    let technologies: Vec<String> = detected
+        .into_iter()
+        .filter(|tech| {
+            // Nothing to write for technologies without a template
+            let Some(content) = template(tech) else { return false };
+            let Some(samples) = sample_paths(content) else { return true };
+            let inherited = !ancestors.is_empty()
+                && !samples.is_empty()
+                && samples.iter().all(|(path, is_dir)| {
+                    ancestors.iter().any(|(prefix, matcher)| {
+                        matcher.matched_path_or_any_parents(prefix.join(path), *is_dir).is_ignore()
+                    })
+                });
+            !inherited
+        })
+        .collect();
+
+    let content: String =
+        technologies.iter().filter_map(|t| template(t)).collect::<Vec<_>>().join("\n");
+    planned.push((root.to_path_buf(), generator::compile(&content)));
+    technologies
+}
+
+/// A representative path (and whether it is a directory) for each ignore pattern
+/// in `template`, relative to the template's directory.
+///
+/// Returns `None` if the template cannot be inherited from a parent file at all:
+/// it has anchored patterns (`/vendor/`, `/[Ll]ibrary/`, `docs/api/`), negations,
+/// or patterns too unusual to sample.
+fn sample_paths(template: &str) -> Option<Vec<(String, bool)>> {
+    let mut samples = Vec::new();
+    for line in template.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('!') || line.contains('\\') {
+            return None;
+        }
+        let is_dir = line.ends_with('/');
+        let pattern = line.trim_end_matches('/');
+        // A leading or middle slash anchors the pattern to the file's directory
+        let pattern = pattern.strip_prefix("**/").unwrap_or(pattern);
+        if pattern.is_empty() || pattern.contains('/') {
+            return None;
+        }
+
+        // One concrete name per pattern: `*`/`?` become `x`, `[Bb]in` becomes `Bin`
+        let mut path = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' | '?' => path.push('x'),
+                '[' => {
+                    let first = chars.next().filter(|c| !matches!(c, '!' | '^' | ']'))?;
+                    path.push(first);
+                    chars.by_ref().find(|&c| c == ']')?;
+                }
+                c => path.push(c),
+            }
+        }
+        samples.push((path, is_dir));
+    }
+    Some(samples)
+}
+
+/// Append technologies not yet present, keeping the list free of duplicates.
+fn merge(technologies: &mut Vec<String>, extra: &[String]) {
+    for tech in extra {
+        if !technologies.contains(tech) {
+            technologies.push(tech.clone());
+        }
+    }
+}
+
+/// Warn when a scan budget cut the walk short.
+fn warn_if_truncated(root: &Path, analysis: &analyzer::Analysis) {
+    if analysis.truncated {
+        eprintln!(
+            "Warning: Scan of {} stopped after {} entries (budget reached); results may be incomplete",
+            root.display(),
+            analysis.entries_scanned
+        );
+    }
+}
+
+/// A pattern suggested for artefacts that no template covers.
+struct Suggestion {
+    pattern: String,
+    kind: ArtifactKind,
+    files: usize,
+    bytes: u64,
+}
+
+/// Group uncovered artefacts by suggested pattern, in path order.
+fn suggest_patterns(content: &str, artifacts: &[Artifact]) -> Vec<Suggestion> {
+    let existing = generator::compile(content);
+    let is_uncovered = |artifact: &&Artifact| {
+        let is_dir = artifact.path.ends_with('/');
+        let path = artifact.path.trim_end_matches('/');
+        !existing.matched_path_or_any_parents(path, is_dir).is_ignore()
+    };
+
+    // Files inside a suggested directory need no pattern of their own
+    let (dirs, files): (Vec<_>, Vec<_>) =
+        artifacts.iter().filter(is_uncovered).partition(|a| a.path.ends_with('/'));
+    let mut with_dirs = content.to_string();
+    for dir in &dirs {
+        with_dirs.push_str(&dir.pattern);
+        with_dirs.push('\n');
+    }
+    let covered = generator::compile(&with_dirs);
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for artifact in dirs.into_iter().chain(files) {
+        let is_dir = artifact.path.ends_with('/');
+        let path = artifact.path.trim_end_matches('/');
+        if !is_dir && covered.matched_path_or_any_parents(path, false).is_ignore() {
+            continue;
+        }
+        match suggestions.iter_mut().find(|s| s.pattern == artifact.pattern) {
+            Some(s) => {
+                s.files += 1;
+                s.bytes += artifact.size;
+            }
+            None => suggestions.push(Suggestion {
+                pattern: artifact.pattern.clone(),
+                kind: artifact.kind,
+                files: 1,
+                bytes: artifact.size,
+            }),
         }
     }
 
-    if !any_added {
-        return Err("No templates found for specified technologies".to_string());
+    suggestions
+}
+
+/// Add suggestions to the custom section when accepted.
+fn accept_suggestions(generator: Generator, accept: bool, suggestions: &[Suggestion]) -> Generator {
+    if !accept {
+        return generator;
+    }
+    generator.custom(suggestions.iter().map(|s| s.pattern.clone()))
+}
+
+/// Build content, mapping errors to CLI messages.
+fn build(generator: &Generator) -> Result<String, String> {
+    generator.build().map_err(|e| e.to_string())
+}
+
+/// List suggestions that were not written to the file.
+fn print_suggestions(suggestions: &[Suggestion]) {
+    if suggestions.is_empty() {
+        return;
+    }
+
+    let width = suggestions.iter().map(|s| s.pattern.len()).max().unwrap_or(0);
+    println!("\nSuggested patterns for files not covered by any template:");
+    for s in suggestions {
+        let files = if s.files == 1 { "1 file".to_string() } else { format!("{} files", s.files) };
+        let size = match s.bytes {
+            0 => String::new(),
+            bytes if bytes < 1024 * 1024 => format!(", {:.1} KB", bytes as f64 / 1024.0),
+            bytes => format!(", {:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+        };
+        println!("  {:<width$}  {} ({}{})", s.pattern, s.kind.describe(), files, size);
+    }
+    println!("Re-run with --accept-suggestions to add them to a custom section.");
+}
+
+/// Warn loudly about secrets that git already tracks - ignoring them has no effect.
+fn warn_tracked_secrets(root: &Path, secrets: &[String]) {
+    if secrets.is_empty() {
+        return;
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["ls-files", "-z", "--"])
+        .args(secrets)
+        .output();
+
+    // Not a git repository or git not installed: nothing can be tracked
+    let Ok(output) = output else { return };
+    if !output.status.success() {
+        return;
+    }
+
+    let tracked: Vec<_> = output
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|p| !p.is_empty())
+        .map(|p| String::from_utf8_lossy(p).into_owned())
+        .collect();
+    if tracked.is_empty() {
+        return;
+    }
+
+    eprintln!();
+    eprintln!("WARNING: These secret files are already tracked by git and are NOT ignored:");
+    for path in &tracked {
+        eprintln!("  - {}", path);
     }
+    eprintln!("Remove them from the index with `git rm --cached <file>` and rotate any");
+    eprintln!("credentials they contain - they remain in the repository history.");
+}
+
+/// Path relative to the working directory for messages ("." for the root itself).
+fn display_path(base: &Path, path: &Path) -> String {
+    match path.strip_prefix(base) {
+        Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_patterns() {
+        let artifact = |path: &str, pattern: &str, size| Artifact {
+            path: path.to_string(),
+            size,
+            kind: ArtifactKind::Archive,
+            pattern: pattern.to_string(),
+        };
+        let artifacts = [
+            artifact("a.zip", "*.zip", 10),
+            artifact("dist/b.zip", "*.zip", 5),
+            artifact("backup.tar.gz", "*.tar.gz", 1),
+            artifact("logs.7z", "*.7z", 1),
+            artifact("coverage/", "coverage/", 0),
+            artifact("coverage/report.zip", "*.zip", 1),
+        ];
+
+        let suggestions = suggest_patterns("*.7z\n", &artifacts);
+        let found: Vec<_> =
+            suggestions.iter().map(|s| (s.pattern.as_str(), s.files, s.bytes)).collect();
+        assert_eq!(found, vec![("coverage/", 1, 0), ("*.zip", 2, 15), ("*.tar.gz", 1, 1)]);
 
-    Ok(content)
+        let content = build(&accept_suggestions(Generator::new(), true, &suggestions)).unwrap();
+        assert!(content.contains("### Custom ###\ncoverage/\n*.zip\n*.tar.gz\n"));
+    }
+
+    #[test]
+    fn test_nested_roots_inherit_only_unanchored_templates() {
+        let template = |tech: &str| match tech {
+            "Rust" => Some("# Build output\ntarget/\n**/*.rs.bk\n"),
+            "Elixir" => Some("/_build/\n/deps/\n*.ez\n"),
+            "Node" => Some("node_modules/\n"),
+            _ => None,
+        };
+        let techs = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let mut planned = Vec::new();
+        let mut plan = |root: &str, detected: &[&str]| {
+            uncovered(Path::new(root), techs(detected), &mut planned, template)
+        };
+
+        assert_eq!(plan("/repo", &["Elixir", "Rust", "Unknown"]), techs(&["Elixir", "Rust"]));
+        // `target/` applies at any depth; `/_build/` only next to the root file
+        assert_eq!(plan("/repo/apps/api", &["Elixir", "Rust"]), techs(&["Elixir"]));
+        assert!(plan("/repo/crates/a", &["Rust"]).is_empty());
+        // Siblings do not inherit from each other
+        assert_eq!(plan("/repo/apps/web", &["Node"]), techs(&["Node"]));
+        assert!(plan("/repo/apps/web/packages/ui", &["Node", "Rust"]).is_empty());
+    }
+
+    #[test]
+    fn test_unsampled_templates_are_not_inherited() {
+        let template = |tech: &str| match tech {
+            "Node" => Some("node_modules/\n"),
+            "C++" => Some("/[Bb]uild/\n"),
+            "Unity" => Some("[Ll]ibrary/\n*.unitypackage\n"),
+            "Empty" => Some("# nothing\n"),
+            _ => None,
+        };
+        let techs = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let mut planned = Vec::new();
+        let mut plan = |root: &str, detected: &[&str]| {
+            uncovered(Path::new(root), techs(detected), &mut planned, template)
+        };
+
+        assert_eq!(plan("/repo", &["Node", "C++", "Unity"]), techs(&["Node", "C++", "Unity"]));
+        // Anchored bracket patterns never reach nested roots
+        assert_eq!(plan("/repo/svc", &["C++", "Empty"]), techs(&["C++", "Empty"]));
+        // Unanchored bracket patterns are checked against a sample name
+        assert!(plan("/repo/game", &["Unity"]).is_empty());
+        assert_eq!(
+            sample_paths("[Ll]ibrary/\n**/*.bk\n").unwrap(),
+            [("Library".to_string(), true), ("x.bk".to_string(), false)]
+        );
+        assert!(sample_paths("*.log\n!keep.log\n").is_none());
+    }
 }
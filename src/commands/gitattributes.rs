@@ -0,0 +1,322 @@
+use super::ScanOptions;
+use crate::config;
+use gitignore_gen::analyzer::{self, Artifact, ArtifactKind};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+
+/// Attribute lines contributed by a detected technology.
+struct Attributes {
+    /// Technology name as reported by the analyzer
+    name: &'static str,
+    /// Text, eol and diff settings
+    text: &'static [&'static str],
+    /// Binary assets stored with Git LFS
+    lfs: &'static [&'static str],
+    /// Files marked as generated for GitHub Linguist
+    generated: &'static [&'static str],
+}
+
+/// Normalisation applied to every repository.
+const BASE_ATTRIBUTES: &[&str] = &[
+    "* text=auto",
+    "*.sh text eol=lf",
+    "*.bat text eol=crlf",
+    "*.cmd text eol=crlf",
+    "*.ps1 text eol=crlf",
+];
+
+const ATTRIBUTES: &[Attributes] = &[
+    // === Languages ===
+    Attributes { name: "Rust", text: &["*.rs text diff=rust"], lfs: &[], generated: &[] },
+    Attributes {
+        name: "Go",
+        text: &["*.go text eol=lf diff=golang"],
+        lfs: &[],
+        generated: &["*.pb.go", "*_gen.go"],
+    },
+    Attributes { name: "Python", text: &["*.py text diff=python"], lfs: &[], generated: &[] },
+    Attributes {
+        name: "Node",
+        text: &["*.js text", "*.ts text", "*.json text", "*.css text diff=css"],
+        lfs: &[],
+        generated: &["package-lock.json", "yarn.lock", "pnpm-lock.yaml", "*.min.js", "*.min.css"],
+    },
+    Attributes {
+        name: "Java",
+        text: &["*.java text diff=java", "*.jar binary"],
+        lfs: &[],
+        generated: &[],
+    },
+    Attributes {
+        name: "Kotlin",
+        text: &["*.kt text diff=kotlin", "*.kts text diff=kotlin"],
+        lfs: &[],
+        generated: &[],
+    },
+    Attributes { name: "Swift", text: &["*.swift text"], lfs: &[], generated: &[] },
+    Attributes {
+        name: "C",
+        text: &["*.c text diff=cpp", "*.h text diff=cpp"],
+        lfs: &[],
+        generated: &[],
+    },
+    Attributes {
+        name: "C++",
+        text: &[
+            "*.cpp text diff=cpp",
+            "*.cc text diff=cpp",
+            "*.hpp text diff=cpp",
+            "*.h text diff=cpp",
+        ],
+        lfs: &[],
+        generated: &[],
+    },
+    Attributes { name: "Ruby", text: &["*.rb text diff=ruby"], lfs: &[], generated: &[] },
+    Attributes {
+        name: "Dart",
+        text: &["*.dart text eol=lf"],
+        lfs: &[],
+        generated: &["*.g.dart", "*.freezed.dart"],
+    },
+    Attributes {
+        name: "Elixir",
+        text: &["*.ex text diff=elixir", "*.exs text diff=elixir"],
+        lfs: &[],
+        generated: &[],
+    },
+    Attributes {
+        name: "Perl",
+        text: &["*.pl text diff=perl", "*.pm text diff=perl"],
+        lfs: &[],
+        generated: &[],
+    },
+    // === Build Tools ===
+    Attributes {
+        name: "Gradle",
+        text: &["gradlew text eol=lf", "gradlew.bat text eol=crlf"],
+        lfs: &[],
+        generated: &[],
+    },
+    // === Frameworks ===
+    Attributes { name: "Rails", text: &[], lfs: &[], generated: &["db/schema.rb"] },
+    // === Game Engines ===
+    Attributes {
+        name: "Unity",
+        text: &[
+            "*.cs text diff=csharp",
+            "*.unity merge=unityyamlmerge eol=lf",
+            "*.prefab merge=unityyamlmerge eol=lf",
+            "*.asset merge=unityyamlmerge eol=lf",
+            "*.meta merge=unityyamlmerge eol=lf",
+            "*.mat merge=unityyamlmerge eol=lf",
+            "*.anim merge=unityyamlmerge eol=lf",
+            "*.controller merge=unityyamlmerge eol=lf",
+        ],
+        lfs: &[
+            "*.psd",
+            "*.png",
+            "*.jpg",
+            "*.tga",
+            "*.tif",
+            "*.exr",
+            "*.fbx",
+            "*.obj",
+            "*.blend",
+            "*.wav",
+            "*.mp3",
+            "*.ogg",
+            "*.mp4",
+            "*.ttf",
+            "*.otf",
+            "*.dll",
+            "*.unitypackage",
+        ],
+        generated: &[],
+    },
+    Attributes {
+        name: "UnrealEngine",
+        text: &["*.cpp text diff=cpp", "*.h text diff=cpp", "*.ini text"],
+        lfs: &[
+            "*.uasset", "*.umap", "*.upk", "*.udk", "*.psd", "*.png", "*.tga", "*.fbx", "*.wav",
+            "*.mp4",
+        ],
+        generated: &[],
+    },
+    Attributes {
+        name: "Godot",
+        text: &[
+            "*.gd text eol=lf",
+            "*.tscn text eol=lf",
+            "*.tres text eol=lf",
+            "*.godot text eol=lf",
+            "*.import text eol=lf",
+        ],
+        lfs: &[
+            "*.png", "*.jpg", "*.webp", "*.wav", "*.ogg", "*.mp3", "*.glb", "*.fbx", "*.blend",
+            "*.ttf", "*.otf",
+        ],
+        generated: &[],
+    },
+    // === Editors & IDEs ===
+    Attributes {
+        name: "VisualStudio",
+        text: &["*.sln text eol=crlf", "*.csproj text eol=crlf"],
+        lfs: &[],
+        generated: &[],
+    },
+];
+
+/// Generate a .gitattributes file for detected or specified technologies
+pub fn gitattributes(
+    technologies: &[String],
+    force: bool,
+    dry_run: bool,
+    scan: &ScanOptions,
+) -> Result<(), String> {
+    let path = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let config = config::load(&path)?;
+
+    let attributes_path = path.join(".gitattributes");
+    if attributes_path.exists() && !force && !dry_run {
+        return Err("A .gitattributes file already exists. Use --force to overwrite.".to_string());
+    }
+
+    let mut options = scan.analyze_options(&config.analyzer)?;
+    options.find_secrets = false;
+    let analysis = analyzer::analyze_project(&path, &options);
+
+    let technologies = if technologies.is_empty() {
+        analysis.technologies
+    } else {
+        let mut valid = Vec::new();
+        for tech in technologies {
+            match ATTRIBUTES.iter().find(|a| a.name.eq_ignore_ascii_case(tech)) {
+                Some(attributes) => valid.push(attributes.name.to_string()),
+                None => eprintln!("Warning: No attributes for '{}', skipping", tech),
+            }
+        }
+        if valid.is_empty() {
+            return Err("None of the specified technologies have attributes.".to_string());
+        }
+        valid
+    };
+
+    let content = build_gitattributes(&technologies, &analysis.artifacts);
+    if dry_run {
+        print!("{}", content);
+        return Ok(());
+    }
+
+    fs::write(&attributes_path, &content)
+        .map_err(|e| format!("Failed to write .gitattributes file: {}", e))?;
+
+    if technologies.is_empty() {
+        println!("Generated .gitattributes with default line-ending normalisation");
+    } else {
+        println!("Generated .gitattributes for: {}", technologies.join(", "));
+    }
+    if content.contains("filter=lfs") {
+        println!("Some files are tracked with Git LFS; run `git lfs install` if you haven't yet.");
+    }
+    Ok(())
+}
+
+/// Combine base, per-technology and detected-artefact attributes.
+fn build_gitattributes(technologies: &[String], artifacts: &[Artifact]) -> String {
+    let mut content = String::new();
+    content.push_str("# Generated by gitignore-gen\n");
+    content.push_str(&format!("# Technologies: {}\n\n", technologies.join(", ")));
+
+    // A pattern gets its attributes from the first section that mentions it
+    let mut seen = HashSet::new();
+    let mut push_section = |title: &str, lines: Vec<String>| {
+        let lines: Vec<_> = lines
+            .into_iter()
+            .filter(|line| seen.insert(line.split_whitespace().next().unwrap_or("").to_string()))
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        content.push_str(&format!("### {} ###\n", title));
+        for line in lines {
+            content.push_str(&line);
+            content.push('\n');
+        }
+        content.push('\n');
+    };
+
+    push_section("Defaults", BASE_ATTRIBUTES.iter().map(|l| l.to_string()).collect());
+
+    for tech in technologies {
+        let Some(attributes) = ATTRIBUTES.iter().find(|a| a.name == tech) else { continue };
+        let lines = attributes
+            .text
+            .iter()
+            .map(|l| l.to_string())
+            .chain(attributes.lfs.iter().map(|p| lfs_line(p)))
+            .chain(attributes.generated.iter().map(|p| format!("{} linguist-generated", p)))
+            .collect();
+        push_section(tech, lines);
+    }
+
+    // Large binaries and generated files found in this tree
+    let lines = artifacts
+        .iter()
+        .filter_map(|a| {
+            let pattern = a.pattern.trim_end_matches('/');
+            match a.kind {
+                ArtifactKind::LargeBinary => Some(lfs_line(pattern)),
+                ArtifactKind::Generated if a.pattern.ends_with('/') => {
+                    Some(format!("{}/** linguist-generated", pattern))
+                }
+                ArtifactKind::Generated => Some(format!("{} linguist-generated", pattern)),
+                ArtifactKind::Archive | ArtifactKind::Media => None,
+            }
+        })
+        .collect();
+    push_section("Detected", lines);
+
+    content
+}
+
+fn lfs_line(pattern: &str) -> String {
+    format!("{} filter=lfs diff=lfs merge=lfs -text", pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_gitattributes_for_unity() {
+        let artifacts = [Artifact {
+            path: "Build/app.min.js".to_string(),
+            size: 10,
+            kind: ArtifactKind::Generated,
+            pattern: "*.min.js".to_string(),
+        }];
+        let content = build_gitattributes(&["Unity".to_string()], &artifacts);
+
+        assert!(content.contains("### Defaults ###\n* text=auto\n"));
+        assert!(content.contains("\n*.psd filter=lfs diff=lfs merge=lfs -text\n"));
+        assert!(content.contains("\n*.unity merge=unityyamlmerge eol=lf\n"));
+        assert!(content.contains("### Detected ###\n*.min.js linguist-generated\n"));
+    }
+
+    #[test]
+    fn test_build_gitattributes_skips_repeated_patterns() {
+        let technologies = ["C".to_string(), "C++".to_string(), "Node".to_string()];
+        let artifacts = [Artifact {
+            path: "dist/app.min.js".to_string(),
+            size: 10,
+            kind: ArtifactKind::Generated,
+            pattern: "*.min.js".to_string(),
+        }];
+        let content = build_gitattributes(&technologies, &artifacts);
+
+        assert_eq!(content.matches("\n*.h text diff=cpp\n").count(), 1);
+        assert_eq!(content.matches("*.min.js linguist-generated").count(), 1);
+        assert!(!content.contains("### Detected ###"));
+    }
+}
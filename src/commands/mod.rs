@@ -1,7 +1,13 @@
 mod generate;
+mod gitattributes;
+mod notify;
+mod scan;
 mod uninstall;
 mod update;
 
-pub use generate::generate;
+pub use generate::{generate, GenerateOptions};
+pub use gitattributes::gitattributes;
+pub use notify::{notify_if_outdated, refresh_update_cache, REFRESH_COMMAND};
+pub use scan::ScanOptions;
 pub use uninstall::uninstall;
-pub use update::update;
+pub use update::{check, set_offline, update, UpdateOptions};
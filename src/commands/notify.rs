@@ -0,0 +1,167 @@
+//! Daily background check for new releases.
+//!
+//! `generate` prints a one-line notice when the cached latest version is newer
+//! than this binary. The cache is refreshed at most once a day by a detached
+//! child process, so generation never waits on the network.
+
+use super::update;
+use crate::config;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    env, fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Hidden subcommand the detached refresh process runs.
+pub const REFRESH_COMMAND: &str = "refresh-update-cache";
+
+/// Seconds between two release checks.
+const CHECK_INTERVAL: u64 = 24 * 60 * 60;
+
+/// Cached result of the last release check.
+#[derive(Default, Serialize, Deserialize)]
+struct UpdateCache {
+    /// Unix time of the last attempt, successful or not
+    checked_at: u64,
+    /// Latest release version found
+    latest_version: Option<String>,
+}
+
+/// Print a notice if a newer release is cached and refresh a stale cache in the background.
+pub fn notify_if_outdated() {
+    if !enabled() {
+        return;
+    }
+    let Some(path) = cache_file() else { return };
+    let cache = read_cache(&path);
+
+    if let Some(notice) = notice(&cache, &update::current_version()) {
+        eprintln!("\n{notice}");
+    }
+
+    if is_stale(&cache, now()) {
+        // Detached: the check finishes on its own after this process exits
+        if let Ok(exe) = env::current_exe() {
+            let _ = Command::new(exe)
+                .arg(REFRESH_COMMAND)
+                .env("GITIGNORE_GEN_HTTP_TIMEOUT", "10")
+                // Away from the project, which has no say in where updates come from
+                .current_dir(env::temp_dir())
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        }
+    }
+}
+
+/// Query the latest release and store it in the cache. Errors are silent.
+pub fn refresh_update_cache() {
+    let Some(path) = cache_file() else { return };
+    let mut cache = read_cache(&path);
+
+    // Record the attempt first so failures are not retried on every run
+    cache.checked_at = now();
+    write_cache(&path, &cache);
+
+    if let Ok(latest) = update::latest_version() {
+        cache.latest_version = Some(latest.to_string());
+        write_cache(&path, &cache);
+    }
+}
+
+/// Opted out via `GITIGNORE_GEN_NO_UPDATE_CHECK`, `notify = false` in the user
+/// config, offline mode or CI.
+fn enabled() -> bool {
+    let set = |name: &str| env::var(name).is_ok_and(|v| !v.trim().is_empty());
+    if set("GITIGNORE_GEN_NO_UPDATE_CHECK") || set("CI") || update::is_offline() {
+        return false;
+    }
+    let notify = config::load_user().ok().and_then(|config| config.update.notify);
+    notify.unwrap_or(true)
+}
+
+/// `$XDG_CACHE_HOME/gitignore-gen/update-check.json`, falling back to the
+/// platform cache directory.
+fn cache_file() -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = var("XDG_CACHE_HOME").or_else(|| {
+        if cfg!(windows) {
+            var("LOCALAPPDATA")
+        } else if cfg!(target_os = "macos") {
+            var("HOME").map(|home| home.join("Library/Caches"))
+        } else {
+            var("HOME").map(|home| home.join(".cache"))
+        }
+    })?;
+    Some(base.join("gitignore-gen").join("update-check.json"))
+}
+
+fn read_cache(path: &PathBuf) -> UpdateCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(path: &PathBuf, cache: &UpdateCache) {
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(content) = serde_json::to_string(cache) {
+        let _ = fs::write(path, content);
+    }
+}
+
+fn is_stale(cache: &UpdateCache, now: u64) -> bool {
+    now.saturating_sub(cache.checked_at) >= CHECK_INTERVAL
+}
+
+fn notice(cache: &UpdateCache, current: &semver::Version) -> Option<String> {
+    let latest = update::parse_version(cache.latest_version.as_deref()?).ok()?;
+    if update::compare_versions(current, &latest) != Ordering::Less {
+        return None;
+    }
+    Some(format!(
+        "A new version of gitignore-gen is available: v{current} → v{latest}. \
+         Run `gitignore-gen update` to install it."
+    ))
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(checked_at: u64, latest: Option<&str>) -> UpdateCache {
+        UpdateCache { checked_at, latest_version: latest.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = 1_700_000_000;
+        assert!(is_stale(&UpdateCache::default(), now));
+        assert!(is_stale(&cache(now - CHECK_INTERVAL, None), now));
+        assert!(!is_stale(&cache(now - 60, None), now));
+        // A clock that went backwards does not trigger a check
+        assert!(!is_stale(&cache(now + 60, None), now));
+    }
+
+    #[test]
+    fn test_notice() {
+        let current = semver::Version::new(2, 0, 2);
+        assert!(notice(&cache(0, None), &current).is_none());
+        assert!(notice(&cache(0, Some("2.0.2")), &current).is_none());
+        assert!(notice(&cache(0, Some("2.0.1")), &current).is_none());
+        assert!(notice(&cache(0, Some("garbage")), &current).is_none());
+
+        let message = notice(&cache(0, Some("2.1.0")), &current).unwrap();
+        assert!(message.contains("v2.0.2 → v2.1.0"));
+    }
+}
@@ -0,0 +1,76 @@
+//! Scan settings shared by the commands that analyze the project.
+
+use crate::config::AnalyzerConfig;
+use gitignore_gen::analyzer::{self, AnalyzeOptions};
+use std::time::Duration;
+
+/// Scan flags accepted by `generate` and `gitattributes`.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct ScanOptions {
+    /// Maximum directory depth to scan (default: 3)
+    #[arg(long, value_name = "N")]
+    pub depth: Option<usize>,
+
+    /// Skip paths matching this glob while scanning (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Scan paths matching this glob even if skipped by default (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip paths ignored by existing .gitignore/.ignore files while scanning
+    #[arg(long)]
+    pub respect_gitignore: bool,
+
+    /// Stop scanning after this many files and directories
+    #[arg(long, value_name = "N")]
+    pub max_files: Option<usize>,
+
+    /// Stop scanning after this many seconds
+    #[arg(long, value_name = "SECS")]
+    pub scan_timeout: Option<u64>,
+}
+
+impl ScanOptions {
+    /// Analyzer options from these flags, falling back to the `[analyzer]` config.
+    /// Glob lists from both are combined.
+    pub fn analyze_options(&self, config: &AnalyzerConfig) -> Result<AnalyzeOptions, String> {
+        let depth = self.depth.or(config.depth).unwrap_or(analyzer::DEFAULT_MAX_DEPTH);
+        let exclude = [config.exclude.clone(), self.exclude.clone()].concat();
+        let include = [config.include.clone(), self.include.clone()].concat();
+
+        let mut options =
+            AnalyzeOptions::new(depth, &exclude, &include).map_err(|e| e.to_string())?;
+        options.respect_ignore =
+            self.respect_gitignore || config.respect_gitignore.unwrap_or(false);
+        options.max_entries = self.max_files.or(config.max_files);
+        options.time_budget = self.scan_timeout.or(config.scan_timeout).map(Duration::from_secs);
+        if let Some(mb) = config.large_file_mb {
+            options.large_file_size = mb * 1024 * 1024;
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_override_config() {
+        let config = AnalyzerConfig {
+            depth: Some(6),
+            max_files: Some(100),
+            scan_timeout: Some(30),
+            ..AnalyzerConfig::default()
+        };
+        let flags = ScanOptions { depth: Some(2), scan_timeout: Some(5), ..ScanOptions::default() };
+
+        let options = flags.analyze_options(&config).unwrap();
+        assert_eq!(options.max_depth, 2);
+        assert_eq!(options.max_entries, Some(100));
+        assert_eq!(options.time_budget, Some(Duration::from_secs(5)));
+        assert!(!options.respect_ignore);
+    }
+}
@@ -1,17 +1,44 @@
 //! Self-update from GitHub releases.
 //!
 //! Downloads and installs the latest binary directly from GitHub releases
-//! without requiring cargo or any build tools.
+//! without requiring cargo or any build tools. Release assets are either
+//! `.tar.gz` / `.zip` archives containing the binary, or the bare binary.
 
-use serde::Deserialize;
+use crate::config;
+use semver::Version;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::{env, fs, io::Write, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    env, fs,
+    io::{Cursor, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering as AtomicOrdering},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 /// Repository information parsed from Cargo.toml at compile time.
 const REPO: &str = env!("CARGO_PKG_REPOSITORY");
+const GITHUB_API: &str = "https://api.github.com";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// GitHub API response for a release.
+/// Minisign public key that release assets must be signed with, embedded at
+/// build time. Builds without a key refuse to self-update; an empty value (e.g.
+/// an unset CI variable) counts as no key.
+const RELEASE_PUBLIC_KEY: Option<&str> = match option_env!("GITIGNORE_GEN_MINISIGN_KEY") {
+    Some(key) if !key.is_empty() => Some(key),
+    _ => None,
+};
+
+/// Exit code of `update --check` when a newer release exists.
+const UPDATE_AVAILABLE_EXIT_CODE: i32 = 10;
+
+/// Name of the executable inside release archives.
+const BINARY_NAME: &str = if cfg!(windows) { "gitignore-gen.exe" } else { "gitignore-gen" };
+
+/// GitHub API response for a release (also the mirror manifest format).
 #[derive(Deserialize)]
 struct Release {
     tag_name: String,
@@ -22,42 +49,214 @@ struct Release {
 #[derive(Deserialize)]
 struct Asset {
     name: String,
+    /// Absolute, or relative to the manifest on mirrors (defaults to `name`)
+    #[serde(default)]
     browser_download_url: String,
 }
 
+/// Options for the update command, collected from command-line flags.
+pub struct UpdateOptions {
+    /// Install the latest release even if it is older than this binary
+    pub allow_downgrade: bool,
+    /// Install this release tag instead of the latest one
+    pub version: Option<String>,
+    /// Restore the binary replaced by the previous update
+    pub rollback: bool,
+}
+
+/// Result of `update --check`, also printed as JSON.
+#[derive(Serialize)]
+struct CheckResult {
+    current_version: String,
+    latest_version: String,
+    update_available: bool,
+    prerelease: bool,
+}
+
+impl CheckResult {
+    fn exit_code(&self) -> i32 {
+        if self.update_available {
+            UPDATE_AVAILABLE_EXIT_CODE
+        } else {
+            0
+        }
+    }
+}
+
+/// Compare with the latest release without downloading anything.
+/// Returns the exit code: `0`, or [`UPDATE_AVAILABLE_EXIT_CODE`] if an update is available.
+pub fn check(json: bool) -> Result<i32, String> {
+    let result = check_release(&ReleaseSource::configured()?)?;
+    report_check(&result, json)?;
+    Ok(result.exit_code())
+}
+
+fn check_release(source: &ReleaseSource) -> Result<CheckResult, String> {
+    let latest = parse_version(&source.latest()?.tag_name)?;
+    Ok(CheckResult {
+        current_version: VERSION.to_string(),
+        latest_version: latest.to_string(),
+        update_available: compare_versions(&current_version(), &latest) == Ordering::Less,
+        prerelease: !latest.pre.is_empty(),
+    })
+}
+
+fn report_check(result: &CheckResult, json: bool) -> Result<(), String> {
+    if json {
+        let output = serde_json::to_string_pretty(&result)
+            .map_err(|e| format!("Failed to serialize result: {e}"))?;
+        println!("{output}");
+    } else {
+        println!("Current version: v{}", result.current_version);
+        println!("Latest version:  v{}", result.latest_version);
+        if result.update_available {
+            let note = if result.prerelease { " (pre-release)" } else { "" };
+            println!("Update available{note}: run `gitignore-gen update` to install it.");
+        } else {
+            println!("✓ Up to date");
+        }
+    }
+    Ok(())
+}
+
 /// Check for updates and install the latest version.
-pub fn update() -> Result<(), String> {
+pub fn update(options: &UpdateOptions) -> Result<(), String> {
+    if options.rollback {
+        return rollback();
+    }
+
     println!("Current version: v{VERSION}");
-    println!("Checking for updates...\n");
 
-    let (owner, repo) = parse_repo_url(REPO)?;
-    let release = fetch_latest_release(&owner, &repo)?;
-    let latest = release.tag_name.trim_start_matches('v');
+    let source = ReleaseSource::configured()?;
+    let release = match &options.version {
+        Some(version) => {
+            let tag = release_tag(version)?;
+            println!("Fetching release {tag}...\n");
+            source.release(&tag)?
+        }
+        None => {
+            println!("Checking for updates...\n");
+            source.latest()?
+        }
+    };
+    let latest = parse_version(&release.tag_name)?;
+    // An explicitly requested version may be older than this one
+    let allow_downgrade = options.allow_downgrade || options.version.is_some();
 
-    if latest == VERSION {
-        println!("✓ Already on the latest version!");
-        return Ok(());
+    match compare_versions(&current_version(), &latest) {
+        Ordering::Equal if options.version.is_some() => {
+            println!("✓ {} is already installed!", release.tag_name);
+            return Ok(());
+        }
+        Ordering::Equal => {
+            println!("✓ Already on the latest version!");
+            return Ok(());
+        }
+        Ordering::Greater if !allow_downgrade => {
+            println!(
+                "✓ Latest release {} is older than v{VERSION}; not downgrading.",
+                release.tag_name
+            );
+            println!("  Pass --allow-downgrade to install it anyway.");
+            return Ok(());
+        }
+        Ordering::Greater => {
+            println!("  Downgrading: v{VERSION} → {}", release.tag_name);
+        }
+        Ordering::Less => {
+            println!("  New version available: v{VERSION} → {}", release.tag_name);
+        }
+    }
+    if !latest.pre.is_empty() {
+        println!("  Note: {} is a pre-release", release.tag_name);
     }
-
-    println!("  New version available: v{VERSION} → {}", release.tag_name);
 
     let platform = Platform::detect()?;
     let asset = find_asset(&release.assets, &platform)?;
 
     println!("  Downloading {}...", asset.name);
-    let binary = download(&asset.browser_download_url)?;
+    let download = download(&asset.browser_download_url)?;
 
-    // Verify checksum before installing
+    // Verify the asset as published, before unpacking anything
     println!("  Verifying checksum...");
-    verify_checksum(&binary, &asset.name, &release.assets)?;
+    verify_checksum(&download, &asset.name, &release.assets)?;
+    println!("  Verifying signature...");
+    verify_signature(&download, &asset.name, &release.assets, RELEASE_PUBLIC_KEY)?;
+
+    let binary = extract_binary(&asset.name, download)?;
 
     println!("  Installing...");
-    install_binary(&binary)?;
+    install_binary(&binary, &latest)?;
 
     println!("\n✓ Updated to {}!", release.tag_name);
+    println!("  Run `gitignore-gen update --rollback` to restore v{VERSION}.");
+    Ok(())
+}
+
+/// Swap the backup kept by the last update back in.
+fn rollback() -> Result<(), String> {
+    let current = env::current_exe().map_err(|e| format!("Cannot find current exe: {e}"))?;
+    restore_backup(&current)?;
+    println!("✓ Rolled back from v{VERSION} to the previous version");
+    Ok(())
+}
+
+/// Replace `target` with the backup made when it was installed.
+fn restore_backup(target: &Path) -> Result<(), String> {
+    let backup = backup_path(target);
+    if !backup.exists() {
+        return Err("No previous version to roll back to.".to_string());
+    }
+
+    #[cfg(windows)]
+    {
+        // The running executable cannot be deleted, only moved aside
+        let discard = target.with_extension("exe.discard");
+        let _ = fs::remove_file(&discard);
+        fs::rename(target, &discard).map_err(|e| format!("Rollback failed: {e}"))?;
+        if let Err(e) = fs::rename(&backup, target) {
+            let _ = fs::rename(&discard, target);
+            return Err(format!("Rollback failed: {e}"));
+        }
+    }
+
+    #[cfg(unix)]
+    fs::rename(&backup, target).map_err(|e| format!("Rollback failed: {e}"))?;
+
     Ok(())
 }
 
+// ============================================================================
+// Versions
+// ============================================================================
+
+pub(super) fn current_version() -> Version {
+    Version::parse(VERSION).expect("CARGO_PKG_VERSION is valid semver")
+}
+
+/// Parse a release tag such as `v2.0.1` or `2.1.0-rc.1`.
+pub(super) fn parse_version(tag: &str) -> Result<Version, String> {
+    Version::parse(tag.trim().trim_start_matches('v'))
+        .map_err(|e| format!("Release tag '{}' is not a valid version: {e}", tag))
+}
+
+/// Version of the latest release from the configured source.
+pub(super) fn latest_version() -> Result<Version, String> {
+    parse_version(&ReleaseSource::configured()?.latest()?.tag_name)
+}
+
+/// Release tag for a version given on the command line (`2.0.1` or `v2.0.1`).
+fn release_tag(version: &str) -> Result<String, String> {
+    let tag = format!("v{}", version.trim().trim_start_matches('v'));
+    parse_version(&tag).map_err(|_| format!("'{}' is not a valid version", version))?;
+    Ok(tag)
+}
+
+/// Order the installed version against a release, ignoring build metadata.
+pub(super) fn compare_versions(current: &Version, release: &Version) -> Ordering {
+    current.cmp_precedence(release)
+}
+
 // ============================================================================
 // Checksum Verification
 // ============================================================================
@@ -117,6 +316,108 @@ fn verify_checksum(binary: &[u8], asset_name: &str, assets: &[Asset]) -> Result<
     Ok(())
 }
 
+// ============================================================================
+// Signature Verification
+// ============================================================================
+
+/// Verify the detached minisign signature (`<asset>.minisig`) of a download.
+/// Fails closed: a missing key, signature or mismatch aborts the update.
+fn verify_signature(
+    data: &[u8],
+    asset_name: &str,
+    assets: &[Asset],
+    public_key: Option<&str>,
+) -> Result<(), String> {
+    let public_key = public_key.ok_or_else(|| {
+        "This build has no release signing key, so it cannot verify updates. \
+         Download new releases manually."
+            .to_string()
+    })?;
+    let public_key = minisign_verify::PublicKey::from_base64(public_key.trim())
+        .map_err(|e| format!("Invalid embedded public key: {e}"))?;
+
+    let signature_name = format!("{asset_name}.minisig");
+    let signature_asset = assets.iter().find(|a| a.name == signature_name).ok_or_else(|| {
+        format!("No signature ({signature_name}) found in release. Update aborted for security.")
+    })?;
+
+    let signature = download(&signature_asset.browser_download_url)
+        .map_err(|e| format!("Failed to download signature: {e}"))?;
+    let signature = String::from_utf8(signature)
+        .map_err(|e| format!("Signature file contains invalid UTF-8: {e}"))?;
+    let signature = minisign_verify::Signature::decode(&signature)
+        .map_err(|e| format!("Invalid signature file: {e}"))?;
+
+    // Only accept prehashed signatures, as produced by current minisign
+    public_key
+        .verify(data, &signature, false)
+        .map_err(|e| format!("Signature verification failed: {e}. Update aborted."))?;
+
+    println!("  ✓ Signature verified");
+    Ok(())
+}
+
+// ============================================================================
+// Archive Extraction
+// ============================================================================
+
+/// Get the executable out of a downloaded asset.
+/// `.tar.gz` and `.zip` archives are unpacked in memory; anything else is
+/// taken to be the bare binary.
+fn extract_binary(asset_name: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    let name = asset_name.to_lowercase();
+    let binary = if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        extract_from_tar_gz(&data)?
+    } else if name.ends_with(".zip") {
+        extract_from_zip(&data)?
+    } else {
+        return Ok(data);
+    };
+
+    binary.ok_or_else(|| format!("'{}' not found in {}", BINARY_NAME, asset_name))
+}
+
+fn extract_from_tar_gz(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(data));
+    let entries = archive.entries().map_err(|e| format!("Invalid archive: {e}"))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Invalid archive: {e}"))?;
+        let path = entry.path().map_err(|e| format!("Invalid archive: {e}"))?;
+        if !entry.header().entry_type().is_file() || !is_binary_entry(&path) {
+            continue;
+        }
+        let mut binary = Vec::new();
+        entry.read_to_end(&mut binary).map_err(|e| format!("Failed to extract: {e}"))?;
+        return Ok(Some(binary));
+    }
+
+    Ok(None)
+}
+
+fn extract_from_zip(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Invalid archive: {e}"))?;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| format!("Invalid archive: {e}"))?;
+        let is_match = file.is_file() && file.enclosed_name().is_some_and(|p| is_binary_entry(&p));
+        if !is_match {
+            continue;
+        }
+        let mut binary = Vec::new();
+        file.read_to_end(&mut binary).map_err(|e| format!("Failed to extract: {e}"))?;
+        return Ok(Some(binary));
+    }
+
+    Ok(None)
+}
+
+/// Archives may put the binary at the top level or inside a directory.
+fn is_binary_entry(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == BINARY_NAME)
+}
+
 // ============================================================================
 // Platform Detection
 // ============================================================================
@@ -149,14 +450,168 @@ impl Platform {
     fn matches(&self, asset_name: &str) -> bool {
         // Match pattern like "x86_64-pc-windows-msvc" or "aarch64-apple-darwin"
         let name = asset_name.to_lowercase();
-        name.contains(self.arch) && name.contains(self.target_pattern) && !name.contains("sha256")
+        name.contains(self.arch)
+            && name.contains(self.target_pattern)
+            && !name.contains("sha256")
+            && !name.ends_with(".minisig")
     }
 }
 
 // ============================================================================
-// GitHub API
+// Release Source
 // ============================================================================
 
+/// Where releases are published.
+///
+/// Overridable with `GITIGNORE_GEN_MIRROR_URL`, `GITIGNORE_GEN_API_URL` and
+/// `GITIGNORE_GEN_REPO`, or the `[update]` section of the user config file.
+/// The project's `.gitignore-gen.toml` is never consulted: a cloned repository
+/// must not be able to redirect updates (or the GitHub token) to another host.
+#[derive(Debug, PartialEq)]
+enum ReleaseSource {
+    /// GitHub or GitHub Enterprise releases API
+    GitHub {
+        api_url: String,
+        owner: String,
+        repo: String,
+        /// Send `GITHUB_TOKEN`/`GH_TOKEN`: only to api.github.com or an API
+        /// URL given in `GITIGNORE_GEN_API_URL` or the user config
+        send_token: bool,
+    },
+    /// Static mirror serving `latest.json` and `<tag>.json` release manifests
+    Mirror { base_url: reqwest::Url },
+}
+
+impl ReleaseSource {
+    /// Source from the environment and user config, defaulting to this crate's repository.
+    fn configured() -> Result<Self, String> {
+        let config = config::load_user()?.update;
+        Self::from_settings(|name| env::var(name).ok().filter(|v| !v.is_empty()), config)
+    }
+
+    /// Environment variables take precedence over the user config. Both are the
+    /// user's own settings, so an API URL named in either receives the token.
+    fn from_settings(
+        var: impl Fn(&str) -> Option<String>,
+        config: config::UpdateConfig,
+    ) -> Result<Self, String> {
+        let api_url = var("GITIGNORE_GEN_API_URL").or(config.api_url);
+        let explicit_api_url = api_url.is_some();
+
+        let mut source = Self::resolve(
+            var("GITIGNORE_GEN_MIRROR_URL").or(config.mirror_url),
+            api_url,
+            var("GITIGNORE_GEN_REPO").or(config.repo),
+        )?;
+        if let Self::GitHub { send_token, .. } = &mut source {
+            *send_token |= explicit_api_url;
+        }
+        Ok(source)
+    }
+
+    fn resolve(
+        mirror_url: Option<String>,
+        api_url: Option<String>,
+        repo: Option<String>,
+    ) -> Result<Self, String> {
+        if let Some(url) = mirror_url {
+            // Manifests and assets are resolved relative to the mirror directory
+            let url = if url.ends_with('/') { url } else { format!("{url}/") };
+            let base_url = reqwest::Url::parse(&url)
+                .map_err(|e| format!("Invalid mirror URL '{url}': {e}"))?;
+            return Ok(Self::Mirror { base_url });
+        }
+
+        let (owner, repo) = match repo {
+            Some(repo) => match repo.split_once('/') {
+                Some((owner, name)) if !owner.is_empty() && !name.is_empty() => {
+                    (owner.to_string(), name.to_string())
+                }
+                _ => return Err(format!("Invalid repository '{repo}', expected owner/repo")),
+            },
+            None => parse_repo_url(REPO)?,
+        };
+        let api_url = api_url.unwrap_or_else(|| GITHUB_API.to_string());
+        let api_url = api_url.trim_end_matches('/').to_string();
+        let send_token = reqwest::Url::parse(&api_url)
+            .is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some("api.github.com"));
+        Ok(Self::GitHub { api_url, owner, repo, send_token })
+    }
+
+    fn latest(&self) -> Result<Release, String> {
+        match self {
+            Self::GitHub { api_url, owner, repo, .. } => {
+                self.fetch(&format!("{api_url}/repos/{owner}/{repo}/releases/latest"), "latest")
+            }
+            Self::Mirror { base_url } => self.fetch(&format!("{base_url}latest.json"), "latest"),
+        }
+    }
+
+    fn release(&self, tag: &str) -> Result<Release, String> {
+        match self {
+            Self::GitHub { api_url, owner, repo, .. } => {
+                self.fetch(&format!("{api_url}/repos/{owner}/{repo}/releases/tags/{tag}"), tag)
+            }
+            Self::Mirror { base_url } => self.fetch(&format!("{base_url}{tag}.json"), tag),
+        }
+    }
+
+    fn fetch(&self, url: &str, tag: &str) -> Result<Release, String> {
+        self.fetch_with_token(url, tag, github_token())
+    }
+
+    fn fetch_with_token(
+        &self,
+        url: &str,
+        tag: &str,
+        token: Option<String>,
+    ) -> Result<Release, String> {
+        let client = http_client()?;
+        let token = match self {
+            Self::GitHub { send_token: true, .. } => token,
+            _ => None,
+        };
+        let response = send(|| {
+            let request = client.get(url);
+            match &token {
+                Some(token) => request
+                    .bearer_auth(token)
+                    .header(reqwest::header::ACCEPT, "application/vnd.github+json"),
+                None => request,
+            }
+        })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(match tag {
+                "latest" => format!("No releases found at {url}"),
+                _ => format!("Release {tag} not found"),
+            });
+        }
+        if !status.is_success() {
+            return Err(api_error(&response, token.is_some(), SystemTime::now()));
+        }
+
+        let manifest_url = response.url().clone();
+        let mut release =
+            response.json::<Release>().map_err(|e| format!("Failed to parse release: {e}"))?;
+
+        if let Self::Mirror { .. } = self {
+            for asset in &mut release.assets {
+                let path = match asset.browser_download_url.as_str() {
+                    "" => asset.name.as_str(),
+                    url => url,
+                };
+                asset.browser_download_url = manifest_url
+                    .join(path)
+                    .map_err(|e| format!("Invalid asset URL '{path}': {e}"))?
+                    .to_string();
+            }
+        }
+        Ok(release)
+    }
+}
+
 fn parse_repo_url(url: &str) -> Result<(String, String), String> {
     let parts: Vec<&str> =
         url.trim_end_matches('/').trim_end_matches(".git").rsplit('/').take(2).collect();
@@ -167,18 +622,6 @@ fn parse_repo_url(url: &str) -> Result<(String, String), String> {
     }
 }
 
-fn fetch_latest_release(owner: &str, repo: &str) -> Result<Release, String> {
-    let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/latest");
-
-    let response = http_client()?.get(&url).send().map_err(|e| format!("Network error: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {} - No releases found?", response.status()));
-    }
-
-    response.json::<Release>().map_err(|e| format!("Failed to parse release: {e}"))
-}
-
 fn find_asset<'a>(assets: &'a [Asset], platform: &Platform) -> Result<&'a Asset, String> {
     assets.iter().find(|a| platform.matches(&a.name)).ok_or_else(|| {
         format!(
@@ -191,7 +634,8 @@ fn find_asset<'a>(assets: &'a [Asset], platform: &Platform) -> Result<&'a Asset,
 }
 
 fn download(url: &str) -> Result<Vec<u8>, String> {
-    let response = http_client()?.get(url).send().map_err(|e| format!("Download failed: {e}"))?;
+    let client = http_client()?;
+    let response = send(|| client.get(url)).map_err(|e| format!("Download failed: {e}"))?;
 
     if !response.status().is_success() {
         return Err(format!("Download error: {}", response.status()));
@@ -200,39 +644,237 @@ fn download(url: &str) -> Result<Vec<u8>, String> {
     response.bytes().map(|b| b.to_vec()).map_err(|e| format!("Failed to read binary: {e}"))
 }
 
+/// Token for the GitHub API from `GITHUB_TOKEN` or `GH_TOKEN`, raising the rate limit.
+fn github_token() -> Option<String> {
+    ["GITHUB_TOKEN", "GH_TOKEN"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .map(|token| token.trim().to_string())
+        .find(|token| !token.is_empty())
+}
+
+/// Delays before retrying a request that failed with a server error.
+const RETRY_DELAYS: [Duration; 3] =
+    [Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(4)];
+
+/// Send a request, retrying transient 5xx errors with backoff.
+fn send(
+    request: impl Fn() -> reqwest::blocking::RequestBuilder,
+) -> Result<reqwest::blocking::Response, String> {
+    send_with_retries(request, &RETRY_DELAYS)
+}
+
+fn send_with_retries(
+    request: impl Fn() -> reqwest::blocking::RequestBuilder,
+    delays: &[Duration],
+) -> Result<reqwest::blocking::Response, String> {
+    let mut delays = delays.iter();
+    loop {
+        let response = request().send().map_err(|e| format!("Network error: {e}"))?;
+        match delays.next() {
+            Some(delay) if response.status().is_server_error() => {
+                eprintln!(
+                    "  Server error ({}), retrying in {}s...",
+                    response.status(),
+                    delay.as_secs()
+                );
+                thread::sleep(*delay);
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// Describe a failed API response, spelling out rate limits and how to lift them.
+fn api_error(
+    response: &reqwest::blocking::Response,
+    authenticated: bool,
+    now: SystemTime,
+) -> String {
+    let status = response.status();
+    let header =
+        |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+
+    let rate_limited = matches!(status.as_u16(), 403 | 429)
+        && header("x-ratelimit-remaining").as_deref() == Some("0");
+    if rate_limited {
+        let limit = header("x-ratelimit-limit").unwrap_or_else(|| "?".to_string());
+        let reset = header("x-ratelimit-reset")
+            .and_then(|r| r.parse::<u64>().ok())
+            .map(|reset| {
+                let reset_at = UNIX_EPOCH + Duration::from_secs(reset);
+                let wait = reset_at.duration_since(now).unwrap_or_default();
+                let minutes = wait.as_secs().div_ceil(60);
+                let (hour, minute) = ((reset % 86400) / 3600, (reset % 3600) / 60);
+                format!(" It resets in {minutes} min (at {hour:02}:{minute:02} UTC).")
+            })
+            .unwrap_or_default();
+        let hint = if authenticated {
+            "Wait for the reset or use a different token."
+        } else {
+            "Set GITHUB_TOKEN or GH_TOKEN to raise the limit."
+        };
+        return format!("GitHub API rate limit exceeded ({limit} requests/hour).{reset} {hint}");
+    }
+
+    match status.as_u16() {
+        401 => "GitHub API rejected the token in GITHUB_TOKEN/GH_TOKEN (401 Unauthorized)".into(),
+        403 if !authenticated => format!(
+            "GitHub API error: {status}. For private repositories set GITHUB_TOKEN or GH_TOKEN."
+        ),
+        _ => format!("Release server error: {status}"),
+    }
+}
+
+/// Set by the global `--offline` flag.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Forbid network access for the rest of the process (`--offline`).
+pub fn set_offline() {
+    OFFLINE.store(true, AtomicOrdering::Relaxed);
+}
+
+/// Whether network access is disabled by `--offline` or `GITIGNORE_GEN_OFFLINE`.
+pub(super) fn is_offline() -> bool {
+    OFFLINE.load(AtomicOrdering::Relaxed)
+        || env::var("GITIGNORE_GEN_OFFLINE").is_ok_and(|v| !v.trim().is_empty())
+}
+
 fn http_client() -> Result<reqwest::blocking::Client, String> {
+    let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+    if is_offline() {
+        return Err("Network access is disabled (--offline)".to_string());
+    }
+
     // Configurable timeout via GITIGNORE_GEN_HTTP_TIMEOUT env var (in seconds)
     let timeout_secs = std::env::var("GITIGNORE_GEN_HTTP_TIMEOUT")
         .ok()
         .and_then(|s| s.parse::<u64>().ok())
         .unwrap_or(60);
 
-    reqwest::blocking::Client::builder()
+    let mut builder = reqwest::blocking::Client::builder()
         .user_agent(concat!("gitignore-gen/", env!("CARGO_PKG_VERSION")))
         .timeout(std::time::Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|e| format!("HTTP client error: {e}"))
+        // Fail fast when there is no route to the server at all
+        .connect_timeout(CONNECT_TIMEOUT.min(Duration::from_secs(timeout_secs)));
+
+    // Never from the project config: a checked-out repository must not add trusted roots
+    let ca_bundle = match var("GITIGNORE_GEN_CA_BUNDLE") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => config::load_user()?.update.ca_bundle,
+    };
+    if let Some(path) = ca_bundle {
+        for certificate in load_ca_bundle(&path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder.build().map_err(|e| format!("HTTP client error: {e}"))
+}
+
+/// Time allowed for establishing a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Root certificates to trust in addition to the system ones.
+fn load_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem =
+        fs::read(path).map_err(|e| format!("Failed to read CA bundle {}: {e}", path.display()))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {e}", path.display()))?;
+    if certificates.is_empty() {
+        return Err(format!("No certificates found in CA bundle {}", path.display()));
+    }
+    Ok(certificates)
 }
 
 // ============================================================================
 // Installation
 // ============================================================================
 
-fn install_binary(binary: &[u8]) -> Result<(), String> {
+/// Where the replaced binary is kept for `update --rollback`.
+fn backup_path(target: &Path) -> PathBuf {
+    if cfg!(windows) {
+        target.with_extension("exe.old")
+    } else {
+        target.with_extension("old")
+    }
+}
+
+fn install_binary(binary: &[u8], version: &Version) -> Result<(), String> {
     let current = env::current_exe().map_err(|e| format!("Cannot find current exe: {e}"))?;
 
     #[cfg(windows)]
-    windows_install(binary, &current)?;
+    windows_install(binary, &current, version)?;
 
     #[cfg(unix)]
-    unix_install(binary, &current)?;
+    unix_install(binary, &current, version)?;
 
     Ok(())
 }
 
+/// How long the new binary gets to answer `--version`.
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run the downloaded binary with `--version` and check that it reports `expected`.
+/// Catches wrong-architecture or corrupt downloads before the old binary is replaced.
+fn verify_runs(path: &Path, expected: &Version) -> Result<(), String> {
+    let mut command = Command::new(path);
+    command
+        .arg("--version")
+        .env_clear()
+        .current_dir(env::temp_dir())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // Windows needs this to load system libraries
+    if let Some(root) = env::var_os("SystemRoot") {
+        command.env("SystemRoot", root);
+    }
+
+    let mut child = command.spawn().map_err(|e| format!("New binary cannot be run: {e}"))?;
+
+    let deadline = Instant::now() + VERIFY_TIMEOUT;
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("New binary cannot be run: {e}"))? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("New binary did not respond to --version".to_string());
+            }
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+    };
+    if !status.success() {
+        return Err(format!("New binary failed to run ({status})"));
+    }
+
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        let _ = stdout.read_to_string(&mut output);
+    }
+    // Output looks like "gitignore-gen 2.0.2"
+    let reported = output.split_whitespace().last().unwrap_or("");
+    match parse_version(reported) {
+        Ok(version) if compare_versions(&version, expected) == Ordering::Equal => Ok(()),
+        _ => Err(format!("New binary reports version '{}', expected {expected}", output.trim())),
+    }
+}
+
 #[cfg(windows)]
-fn windows_install(binary: &[u8], target: &PathBuf) -> Result<(), String> {
-    let backup = target.with_extension("exe.old");
+fn windows_install(binary: &[u8], target: &PathBuf, version: &Version) -> Result<(), String> {
+    let backup = backup_path(target);
+    let temp = target.with_extension("new.exe");
+
+    fs::File::create(&temp)
+        .and_then(|mut f| f.write_all(binary))
+        .map_err(|e| format!("Write failed: {e}"))?;
+
+    if let Err(e) = verify_runs(&temp, version) {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("{e}. Keeping the current version."));
+    }
 
     // Remove old backup (log errors instead of ignoring)
     if let Err(e) = fs::remove_file(&backup) {
@@ -243,19 +885,18 @@ fn windows_install(binary: &[u8], target: &PathBuf) -> Result<(), String> {
 
     fs::rename(target, &backup).map_err(|e| format!("Backup failed: {e}"))?;
 
-    fs::File::create(target)
-        .and_then(|mut f| f.write_all(binary))
-        .map_err(|e| format!("Install failed: {e}"))?;
-
-    // Cleanup backup (log errors instead of ignoring)
-    if let Err(e) = fs::remove_file(&backup) {
-        eprintln!("Warning: Could not cleanup backup {:?}: {}", backup, e);
+    if let Err(e) = fs::rename(&temp, target) {
+        // Put the old binary back rather than leaving nothing installed
+        let _ = fs::rename(&backup, target);
+        return Err(format!("Install failed: {e}"));
     }
+
+    // The backup is kept for `update --rollback`
     Ok(())
 }
 
 #[cfg(unix)]
-fn unix_install(binary: &[u8], target: &PathBuf) -> Result<(), String> {
+fn unix_install(binary: &[u8], target: &PathBuf, version: &Version) -> Result<(), String> {
     use std::os::unix::fs::PermissionsExt;
 
     let temp = target.with_extension("new");
@@ -267,7 +908,517 @@ fn unix_install(binary: &[u8], target: &PathBuf) -> Result<(), String> {
     fs::set_permissions(&temp, fs::Permissions::from_mode(0o755))
         .map_err(|e| format!("Permission denied: {e}"))?;
 
+    if let Err(e) = verify_runs(&temp, version) {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("{e}. Keeping the current version."));
+    }
+
+    // Keep the current binary for `update --rollback`
+    let backup = backup_path(target);
+    fs::copy(target, &backup).map_err(|e| format!("Backup failed: {e}"))?;
+
     fs::rename(&temp, target).map_err(|e| format!("Install failed: {e}"))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    /// Response from a test server handler: status line, extra headers and body.
+    type Reply = (&'static str, Vec<(&'static str, String)>, Vec<u8>);
+
+    /// Serve requests over HTTP on localhost; returns the base URL.
+    /// The handler gets the request path and the request header lines.
+    fn serve_with(mut handler: impl FnMut(&str, &[String]) -> Reply + Send + 'static) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() <= 2 {
+                        break;
+                    }
+                    headers.push(line.trim_end().to_string());
+                }
+
+                let path = request.split_whitespace().nth(1).unwrap_or("");
+                let (status, extra, body) = handler(path, &headers);
+                let mut head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    body.len()
+                );
+                for (name, value) in extra {
+                    head.push_str(&format!("{name}: {value}\r\n"));
+                }
+                head.push_str("\r\n");
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        base
+    }
+
+    /// Serve `routes` (path, body), answering 404 for anything else.
+    fn serve(routes: Vec<(&'static str, Vec<u8>)>) -> String {
+        serve_with(move |path, _| match routes.iter().find(|(p, _)| *p == path) {
+            Some((_, body)) => ("200 OK", Vec::new(), body.clone()),
+            None => ("404 Not Found", Vec::new(), Vec::new()),
+        })
+    }
+
+    fn tar_gz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, data) in files {
+            writer.start_file(*path, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn version(tag: &str) -> Version {
+        parse_version(tag).unwrap()
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        let current = version("2.0.2");
+        assert_eq!(compare_versions(&current, &version("v2.0.2")), Ordering::Equal);
+        assert_eq!(compare_versions(&current, &version("v2.0.10")), Ordering::Less);
+        assert_eq!(compare_versions(&current, &version("v2.0.1")), Ordering::Greater);
+        assert_eq!(compare_versions(&current, &version("2.0.2+build.5")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_pre_releases() {
+        assert_eq!(compare_versions(&version("2.0.2"), &version("v2.1.0-rc.1")), Ordering::Less);
+        assert_eq!(compare_versions(&version("2.1.0-rc.1"), &version("v2.1.0")), Ordering::Less);
+        assert_eq!(
+            compare_versions(&version("2.1.0-rc.2"), &version("v2.1.0-rc.10")),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions(&version("2.0.2"), &version("v2.0.2-beta")), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_parse_invalid_tag() {
+        assert!(parse_version("nightly").unwrap_err().contains("not a valid version"));
+    }
+
+    #[test]
+    fn test_release_tag() {
+        assert_eq!(release_tag("2.0.1").unwrap(), "v2.0.1");
+        assert_eq!(release_tag("v2.1.0-rc.1").unwrap(), "v2.1.0-rc.1");
+        assert!(release_tag("latest").is_err());
+    }
+
+    #[test]
+    fn test_resolve_release_source() {
+        let default = ReleaseSource::resolve(None, None, None).unwrap();
+        assert_eq!(
+            default,
+            ReleaseSource::GitHub {
+                api_url: GITHUB_API.to_string(),
+                owner: "AriajSarkar".to_string(),
+                repo: "gitignore-gen".to_string(),
+                send_token: true,
+            }
+        );
+
+        let enterprise = ReleaseSource::resolve(
+            None,
+            Some("https://ghe.example.com/api/v3/".to_string()),
+            Some("tools/gitignore-gen".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            enterprise,
+            ReleaseSource::GitHub {
+                api_url: "https://ghe.example.com/api/v3".to_string(),
+                owner: "tools".to_string(),
+                repo: "gitignore-gen".to_string(),
+                send_token: false,
+            }
+        );
+
+        assert!(ReleaseSource::resolve(None, None, Some("gitignore-gen".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_configured_api_url_receives_token() {
+        let ghe = "https://ghe.example.com/api/v3";
+        let config =
+            || config::UpdateConfig { api_url: Some(ghe.to_string()), ..Default::default() };
+        let send_token = |source: ReleaseSource| match source {
+            ReleaseSource::GitHub { send_token, .. } => send_token,
+            ReleaseSource::Mirror { .. } => unreachable!(),
+        };
+
+        let from_user_config = ReleaseSource::from_settings(|_| None, config()).unwrap();
+        assert!(send_token(from_user_config));
+
+        let env = |name: &str| (name == "GITIGNORE_GEN_API_URL").then(|| ghe.to_string());
+        let from_env = ReleaseSource::from_settings(env, Default::default()).unwrap();
+        assert!(send_token(from_env));
+    }
+
+    #[test]
+    fn test_token_not_sent_to_configured_host() {
+        let (sender, received) = std::sync::mpsc::channel();
+        let base = serve_with(move |_, headers| {
+            let authorization =
+                headers.iter().any(|h| h.to_lowercase().starts_with("authorization:"));
+            sender.send(authorization).unwrap();
+            let body = br#"{ "tag_name": "v2.1.0", "assets": [] }"#.to_vec();
+            ("200 OK", Vec::new(), body)
+        });
+
+        let source =
+            ReleaseSource::resolve(None, Some(base.clone()), Some("o/r".to_string())).unwrap();
+        let url = format!("{base}/repos/o/r/releases/latest");
+        let release = source.fetch_with_token(&url, "latest", Some("secret".to_string()));
+        assert_eq!(release.unwrap().tag_name, "v2.1.0");
+        assert!(!received.recv().unwrap());
+
+        // Sent once the user names the host explicitly
+        let ReleaseSource::GitHub { api_url, owner, repo, .. } = source else { unreachable!() };
+        let trusted = ReleaseSource::GitHub { api_url, owner, repo, send_token: true };
+        trusted.fetch_with_token(&url, "latest", Some("secret".to_string())).unwrap();
+        assert!(received.recv().unwrap());
+    }
+
+    #[test]
+    fn test_mirror_manifest() {
+        let latest = br#"{
+            "tag_name": "v2.1.0",
+            "assets": [
+                { "name": "SHA256SUMS" },
+                { "name": "gitignore-gen.tar.gz", "browser_download_url": "v2.1.0/gitignore-gen.tar.gz" },
+                { "name": "other", "browser_download_url": "https://cdn.example.com/other" }
+            ]
+        }"#;
+        let base = serve(vec![("/releases/latest.json", latest.to_vec())]);
+        let mirror = ReleaseSource::resolve(Some(format!("{base}/releases")), None, None).unwrap();
+
+        let release = mirror.latest().unwrap();
+        assert_eq!(release.tag_name, "v2.1.0");
+        let urls: Vec<_> = release.assets.iter().map(|a| a.browser_download_url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                format!("{base}/releases/SHA256SUMS").as_str(),
+                format!("{base}/releases/v2.1.0/gitignore-gen.tar.gz").as_str(),
+                "https://cdn.example.com/other",
+            ]
+        );
+
+        assert_eq!(mirror.release("v9.9.9").err().unwrap(), "Release v9.9.9 not found");
+    }
+
+    #[test]
+    fn test_check_against_mirror() {
+        let manifest = |tag: &str| format!(r#"{{ "tag_name": "{tag}", "assets": [] }}"#);
+        let base = serve(vec![
+            ("/current/latest.json", manifest(&format!("v{VERSION}")).into_bytes()),
+            ("/newer/latest.json", manifest("v99.0.0-rc.1").into_bytes()),
+        ]);
+        let mirror =
+            |dir: &str| ReleaseSource::resolve(Some(format!("{base}/{dir}")), None, None).unwrap();
+
+        let current = check_release(&mirror("current")).unwrap();
+        assert!(!current.update_available);
+        assert_eq!(current.latest_version, VERSION);
+        assert_eq!(current.exit_code(), 0);
+
+        let newer = check_release(&mirror("newer")).unwrap();
+        assert!(newer.update_available);
+        assert_eq!(newer.exit_code(), UPDATE_AVAILABLE_EXIT_CODE);
+        assert_eq!(
+            serde_json::to_value(&newer).unwrap(),
+            serde_json::json!({
+                "current_version": VERSION,
+                "latest_version": "99.0.0-rc.1",
+                "update_available": true,
+                "prerelease": true,
+            })
+        );
+    }
+
+    /// Minisign keypair from a fixed seed: (signing key, key id, public key base64).
+    fn minisign_keypair(seed: u8) -> (ed25519_dalek::SigningKey, [u8; 8], String) {
+        use base64::Engine;
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let key_id = [seed; 8];
+        let mut public = b"Ed".to_vec();
+        public.extend_from_slice(&key_id);
+        public.extend_from_slice(signing_key.verifying_key().as_bytes());
+        (signing_key, key_id, base64::engine::general_purpose::STANDARD.encode(public))
+    }
+
+    /// Detached prehashed minisign signature of `data`.
+    fn minisign_sign(key: &ed25519_dalek::SigningKey, key_id: [u8; 8], data: &[u8]) -> String {
+        use base64::Engine;
+        use blake2::Digest as _;
+        use ed25519_dalek::Signer;
+
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let signature = key.sign(&blake2::Blake2b512::digest(data)).to_bytes();
+        let trusted_comment = "timestamp:0\tfile:test";
+        let global = key.sign(&[&signature[..], trusted_comment.as_bytes()].concat()).to_bytes();
+
+        let mut line = b"ED".to_vec();
+        line.extend_from_slice(&key_id);
+        line.extend_from_slice(&signature);
+        format!(
+            "untrusted comment: test\n{}\ntrusted comment: {}\n{}\n",
+            b64.encode(line),
+            trusted_comment,
+            b64.encode(global)
+        )
+    }
+
+    fn signed_release(signature: Option<String>) -> (String, Vec<Asset>) {
+        let mut routes = Vec::new();
+        if let Some(signature) = signature {
+            routes.push(("/app.tar.gz.minisig", signature.into_bytes()));
+        }
+        let base = serve(routes);
+        let assets = vec![
+            Asset { name: "app.tar.gz".into(), browser_download_url: format!("{base}/app.tar.gz") },
+            Asset {
+                name: "app.tar.gz.minisig".into(),
+                browser_download_url: format!("{base}/app.tar.gz.minisig"),
+            },
+        ];
+        (base, assets)
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let (key, key_id, public) = minisign_keypair(7);
+        let (_, assets) = signed_release(Some(minisign_sign(&key, key_id, b"release")));
+
+        assert!(verify_signature(b"release", "app.tar.gz", &assets, Some(&public)).is_ok());
+
+        let tampered = verify_signature(b"tampered", "app.tar.gz", &assets, Some(&public));
+        assert!(tampered.unwrap_err().contains("Signature verification failed"));
+    }
+
+    #[test]
+    fn test_verify_signature_wrong_key() {
+        let (key, key_id, _) = minisign_keypair(7);
+        let (_, other_id, other_public) = minisign_keypair(8);
+        assert_ne!(key_id, other_id);
+        let (_, assets) = signed_release(Some(minisign_sign(&key, key_id, b"release")));
+
+        let result = verify_signature(b"release", "app.tar.gz", &assets, Some(&other_public));
+        assert!(result.unwrap_err().contains("Signature verification failed"));
+    }
+
+    #[test]
+    fn test_verify_signature_fails_closed() {
+        let (key, key_id, public) = minisign_keypair(7);
+        let (_, mut assets) = signed_release(Some(minisign_sign(&key, key_id, b"release")));
+
+        // No embedded key
+        let no_key = verify_signature(b"release", "app.tar.gz", &assets, None);
+        assert!(no_key.unwrap_err().contains("no release signing key"));
+
+        // Signature not published
+        assets.retain(|a| !a.name.ends_with(".minisig"));
+        let missing = verify_signature(b"release", "app.tar.gz", &assets, Some(&public));
+        assert!(missing.unwrap_err().contains("No signature"));
+
+        // Signature listed but not downloadable
+        let (_, assets) = signed_release(None);
+        let unreachable = verify_signature(b"release", "app.tar.gz", &assets, Some(&public));
+        assert!(unreachable.unwrap_err().contains("Failed to download signature"));
+    }
+
+    #[cfg(unix)]
+    fn script(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("gitignore-gen.new");
+        fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let expected = version("v2.1.0");
+
+        let good = script(dir.path(), "echo 'gitignore-gen 2.1.0'");
+        assert!(verify_runs(&good, &expected).is_ok());
+
+        let wrong = script(dir.path(), "echo 'gitignore-gen 2.0.2'");
+        assert!(verify_runs(&wrong, &expected).unwrap_err().contains("expected 2.1.0"));
+
+        let crash = script(dir.path(), "exit 132");
+        assert!(verify_runs(&crash, &expected).unwrap_err().contains("failed to run"));
+
+        let garbage = dir.path().join("garbage");
+        fs::write(&garbage, b"\x7fELF not really").unwrap();
+        assert!(verify_runs(&garbage, &expected).is_err());
+    }
+
+    #[test]
+    fn test_backup_path() {
+        if cfg!(windows) {
+            let target = Path::new(r"C:\tools\gitignore-gen.exe");
+            assert_eq!(backup_path(target), Path::new(r"C:\tools\gitignore-gen.exe.old"));
+        } else {
+            let target = Path::new("/usr/local/bin/gitignore-gen");
+            assert_eq!(backup_path(target), Path::new("/usr/local/bin/gitignore-gen.old"));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("gitignore-gen");
+        let old = "#!/bin/sh\necho 'gitignore-gen 2.0.2'\n";
+        let new = "#!/bin/sh\necho 'gitignore-gen 2.1.0'\n";
+        fs::write(&target, old).unwrap();
+
+        let nothing = restore_backup(&target).unwrap_err();
+        assert_eq!(nothing, "No previous version to roll back to.");
+
+        unix_install(new.as_bytes(), &target, &version("v2.1.0")).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), new);
+        assert_eq!(fs::read_to_string(backup_path(&target)).unwrap(), old);
+
+        restore_backup(&target).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), old);
+        assert!(!backup_path(&target).exists());
+        assert!(restore_backup(&target).is_err());
+    }
+
+    #[test]
+    fn test_retries_server_errors() {
+        let mut requests = 0;
+        let base = serve_with(move |_, _| {
+            requests += 1;
+            match requests {
+                1 | 2 => ("503 Service Unavailable", Vec::new(), Vec::new()),
+                _ => ("200 OK", Vec::new(), b"ok".to_vec()),
+            }
+        });
+        let client = http_client().unwrap();
+        let url = format!("{base}/latest");
+
+        let delays = [Duration::ZERO; 3];
+        let response = send_with_retries(|| client.get(&url), &delays).unwrap();
+        assert_eq!(response.status(), 200);
+
+        // Gives up once the retries are used up
+        let base = serve_with(|_, _| ("502 Bad Gateway", Vec::new(), Vec::new()));
+        let response = send_with_retries(|| client.get(&base), &delays).unwrap();
+        assert_eq!(response.status(), 502);
+    }
+
+    #[test]
+    fn test_rate_limit_error() {
+        let base = serve_with(|_, headers| {
+            let authorized =
+                headers.iter().any(|h| h.to_lowercase() == "authorization: bearer t0k");
+            let limit = if authorized { "5000" } else { "60" };
+            let headers = vec![
+                ("X-RateLimit-Limit", limit.to_string()),
+                ("X-RateLimit-Remaining", "0".to_string()),
+                ("X-RateLimit-Reset", "1700001000".to_string()),
+            ];
+            ("403 Forbidden", headers, Vec::new())
+        });
+        let client = http_client().unwrap();
+        let url = format!("{base}/repos/o/r/releases/latest");
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let response = client.get(&url).send().unwrap();
+        let error = api_error(&response, false, now);
+        assert_eq!(
+            error,
+            "GitHub API rate limit exceeded (60 requests/hour). It resets in 17 min \
+             (at 22:30 UTC). Set GITHUB_TOKEN or GH_TOKEN to raise the limit."
+        );
+
+        let response = client.get(&url).bearer_auth("t0k").send().unwrap();
+        let error = api_error(&response, true, now);
+        assert!(error.contains("(5000 requests/hour)"));
+        assert!(error.ends_with("Wait for the reset or use a different token."));
+    }
+
+    #[test]
+    fn test_forbidden_without_rate_limit() {
+        let base = serve_with(|_, _| ("403 Forbidden", Vec::new(), Vec::new()));
+        let response = http_client().unwrap().get(&base).send().unwrap();
+        let error = api_error(&response, false, SystemTime::now());
+        assert!(error.contains("set GITHUB_TOKEN or GH_TOKEN"));
+    }
+
+    #[test]
+    fn test_load_ca_bundle_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = load_ca_bundle(&dir.path().join("missing.pem")).unwrap_err();
+        assert!(missing.starts_with("Failed to read CA bundle"));
+
+        let empty = dir.path().join("empty.pem");
+        fs::write(&empty, "not a certificate\n").unwrap();
+        assert!(load_ca_bundle(&empty).unwrap_err().starts_with("No certificates found"));
+    }
+
+    #[test]
+    fn test_extract_from_tar_gz() {
+        let nested = format!("dist/{}", BINARY_NAME);
+        let archive = tar_gz(&[("README.md", b"docs"), (&nested, b"binary")]);
+        let binary = extract_binary("gitignore-gen-x86_64-unknown-linux-gnu.tar.gz", archive);
+        assert_eq!(binary.unwrap(), b"binary");
+    }
+
+    #[test]
+    fn test_extract_from_zip() {
+        let archive = zip(&[(BINARY_NAME, b"binary")]);
+        let binary = extract_binary("gitignore-gen-x86_64-pc-windows-msvc.zip", archive);
+        assert_eq!(binary.unwrap(), b"binary");
+    }
+
+    #[test]
+    fn test_extract_missing_binary() {
+        let archive = tar_gz(&[("README.md", b"docs")]);
+        let err = extract_binary("gitignore-gen.tar.gz", archive).unwrap_err();
+        assert!(err.contains("not found"));
+    }
+
+    #[test]
+    fn test_bare_binary_passes_through() {
+        let binary = extract_binary("gitignore-gen-x86_64-unknown-linux-gnu", b"raw".to_vec());
+        assert_eq!(binary.unwrap(), b"raw");
+    }
+}
@@ -0,0 +1,136 @@
+//! Project configuration loaded from `.gitignore-gen.toml`, and user
+//! configuration loaded from `$XDG_CONFIG_HOME/gitignore-gen/config.toml`.
+//!
+//! Every setting is optional; command-line flags take precedence over values
+//! found in the files. Settings that control network access are only read
+//! from the user file, so a checked-out project cannot change them.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration file looked up in the project directory.
+pub const CONFIG_FILE: &str = ".gitignore-gen.toml";
+
+/// Name of the user configuration file inside the `gitignore-gen` config directory.
+pub const USER_CONFIG_FILE: &str = "config.toml";
+
+/// Top-level configuration file layout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Settings for the project analyzer
+    pub analyzer: AnalyzerConfig,
+}
+
+/// User configuration file layout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserConfig {
+    /// Settings for self-update
+    pub update: UpdateConfig,
+}
+
+/// `[analyzer]` section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyzerConfig {
+    /// Maximum directory depth to scan
+    pub depth: Option<usize>,
+    /// Glob patterns for paths that are never scanned
+    pub exclude: Vec<String>,
+    /// Glob patterns for paths scanned even if skipped by default
+    pub include: Vec<String>,
+    /// Skip paths already ignored by existing ignore files
+    pub respect_gitignore: Option<bool>,
+    /// Stop scanning after this many files and directories
+    pub max_files: Option<usize>,
+    /// Stop scanning after this many seconds
+    pub scan_timeout: Option<u64>,
+    /// Add templates for the editor found in the environment
+    pub editor_env: Option<bool>,
+    /// Add `Global/*` operating system templates
+    pub os: Option<OsSetting>,
+    /// Size in MiB from which binaries are reported as large
+    pub large_file_mb: Option<u64>,
+    /// Write suggested patterns for uncovered artefacts to a custom section
+    pub accept_suggestions: Option<bool>,
+}
+
+/// `[update]` section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpdateConfig {
+    /// Base URL of the GitHub API (e.g. a GitHub Enterprise server)
+    pub api_url: Option<String>,
+    /// Repository to fetch releases from, as `owner/repo`
+    pub repo: Option<String>,
+    /// Static mirror serving `latest.json` and per-tag manifests
+    pub mirror_url: Option<String>,
+    /// PEM file with extra root certificates (e.g. a corporate TLS proxy)
+    pub ca_bundle: Option<PathBuf>,
+    /// Print a notice when a newer release is available (checked once a day)
+    pub notify: Option<bool>,
+}
+
+/// `os` setting: `true` for the current platform, or an explicit list.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OsSetting {
+    /// Enable (current platform) or disable
+    Enabled(bool),
+    /// Explicit operating systems (`macos`, `linux`, `windows`)
+    List(Vec<String>),
+}
+
+/// Load the configuration from `dir`, returning defaults if no file exists.
+pub fn load(dir: &Path) -> Result<Config, String> {
+    read(&dir.join(CONFIG_FILE), CONFIG_FILE).map_err(|e| {
+        // Point to the right file rather than reporting an unknown field
+        if e.contains("unknown field `update`") {
+            let user_file = user_config_path()
+                .map_or_else(|| "the user config file".to_string(), |p| p.display().to_string());
+            format!("[update] settings are not read from {CONFIG_FILE}; move them to {user_file}")
+        } else {
+            e
+        }
+    })
+}
+
+/// Path of the user configuration file: `$XDG_CONFIG_HOME/gitignore-gen/config.toml`,
+/// falling back to `~/.config` (or `%APPDATA%` on Windows).
+pub fn user_config_path() -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = var("XDG_CONFIG_HOME").or_else(|| {
+        if cfg!(windows) {
+            var("APPDATA")
+        } else {
+            var("HOME").map(|home| home.join(".config"))
+        }
+    })?;
+    Some(base.join("gitignore-gen").join(USER_CONFIG_FILE))
+}
+
+/// Load the user configuration, returning defaults if no file exists.
+pub fn load_user() -> Result<UserConfig, String> {
+    match user_config_path() {
+        Some(path) => {
+            let label = path.display().to_string();
+            read(&path, &label)
+        }
+        None => Ok(UserConfig::default()),
+    }
+}
+
+fn read<T: DeserializeOwned + Default>(path: &Path, label: &str) -> Result<T, String> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", label, e))?;
+
+    toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", label, e))
+}
@@ -0,0 +1,412 @@
+//! Ignore file generation from the embedded templates.
+//!
+//! [`Generator`] combines the templates for a set of technologies with the
+//! secrets safety net, custom patterns and kind-specific extras:
+//!
+//! ```no_run
+//! use gitignore_gen::analyzer::{self, AnalyzeOptions};
+//! use gitignore_gen::generator::{Generator, IgnoreKind};
+//!
+//! let analysis = analyzer::analyze_project(".".as_ref(), &AnalyzeOptions::default());
+//! let content = Generator::from_analysis(&analysis).kind(IgnoreKind::Dockerignore).build()?;
+//! # Ok::<(), gitignore_gen::generator::Error>(())
+//! ```
+
+use crate::analyzer::{self, Analysis};
+use crate::templates;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fmt;
+
+/// Errors returned by [`Generator::build`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// No embedded template has this name
+    UnknownTemplate(String),
+    /// None of the technologies had a template and there was nothing else to write
+    NoTemplates,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownTemplate(name) => write!(f, "Unknown template '{}'", name),
+            Error::NoTemplates => write!(f, "No templates found for specified technologies"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Kind of ignore file to generate; all share gitignore-style patterns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IgnoreKind {
+    #[default]
+    Gitignore,
+    Dockerignore,
+    Npmignore,
+    Prettierignore,
+    Eslintignore,
+    Helmignore,
+    Gcloudignore,
+}
+
+impl IgnoreKind {
+    /// File written for this kind.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            IgnoreKind::Gitignore => ".gitignore",
+            IgnoreKind::Dockerignore => ".dockerignore",
+            IgnoreKind::Npmignore => ".npmignore",
+            IgnoreKind::Prettierignore => ".prettierignore",
+            IgnoreKind::Eslintignore => ".eslintignore",
+            IgnoreKind::Helmignore => ".helmignore",
+            IgnoreKind::Gcloudignore => ".gcloudignore",
+        }
+    }
+
+    /// Section title and patterns added on top of the templates.
+    fn extras(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            IgnoreKind::Gitignore => ("", &[]),
+            IgnoreKind::Dockerignore => (
+                "Docker",
+                &[".git", ".gitignore", ".dockerignore", "Dockerfile*", "docker-compose*.yml"],
+            ),
+            IgnoreKind::Npmignore => (
+                "npm",
+                &[".github/", "coverage/", "test/", "tests/", "__tests__/", "*.test.*", "*.spec.*"],
+            ),
+            IgnoreKind::Prettierignore => (
+                "Prettier",
+                &["package-lock.json", "pnpm-lock.yaml", "yarn.lock", "*.min.js", "*.min.css"],
+            ),
+            IgnoreKind::Eslintignore => {
+                ("ESLint", &["dist/", "build/", "coverage/", "*.min.js", "*.d.ts"])
+            }
+            IgnoreKind::Helmignore => (
+                "Helm",
+                &[
+                    ".git/",
+                    ".gitignore",
+                    ".hg/",
+                    ".svn/",
+                    "*.swp",
+                    "*.bak",
+                    "*.tmp",
+                    "*.orig",
+                    "*~",
+                ],
+            ),
+            IgnoreKind::Gcloudignore => ("Google Cloud", &[".gcloudignore", ".git", ".gitignore"]),
+        }
+    }
+}
+
+/// Builder for the content of an ignore file.
+#[derive(Debug, Clone, Default)]
+pub struct Generator {
+    technologies: Vec<String>,
+    secrets: Vec<String>,
+    custom: Vec<String>,
+    kind: IgnoreKind,
+    allow_missing: bool,
+}
+
+impl Generator {
+    /// Empty generator for a `.gitignore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generator for the technologies and secrets found by the analyzer.
+    ///
+    /// Detected technologies without a template are skipped.
+    pub fn from_analysis(analysis: &Analysis) -> Self {
+        Self::new()
+            .technologies(&analysis.technologies)
+            .secrets(&analysis.secrets)
+            .allow_missing(true)
+    }
+
+    /// Add a technology by template name (case-insensitive, e.g. `rust`, `Global/macOS`).
+    pub fn technology(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        if !self.technologies.contains(&name) {
+            self.technologies.push(name);
+        }
+        self
+    }
+
+    /// Add several technologies.
+    pub fn technologies<I, S>(self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        names.into_iter().fold(self, |generator, name| generator.technology(name))
+    }
+
+    /// Secret files (paths relative to the ignore file) that must end up ignored.
+    pub fn secrets<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.secrets.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Extra patterns written to a `### Custom ###` section.
+    pub fn custom<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.custom.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Kind of ignore file to generate (default `.gitignore`).
+    pub fn kind(mut self, kind: IgnoreKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Skip technologies without a template instead of failing.
+    pub fn allow_missing(mut self, allow: bool) -> Self {
+        self.allow_missing = allow;
+        self
+    }
+
+    /// Build the file content.
+    pub fn build(&self) -> Result<String, Error> {
+        let mut content = String::new();
+        content.push_str("# Generated by gitignore-gen\n");
+        if !self.technologies.is_empty() {
+            content.push_str(&format!("# Technologies: {}\n", self.technologies.join(", ")));
+        }
+        content.push('\n');
+
+        let mut any_added = false;
+        for tech in &self.technologies {
+            let Some(template) = templates::get_template(tech) else {
+                if self.allow_missing {
+                    continue;
+                }
+                return Err(Error::UnknownTemplate(tech.clone()));
+            };
+            // Skip empty templates
+            if template.trim().is_empty() {
+                continue;
+            }
+            content.push_str(&format!("### {} ###\n", tech));
+            content.push_str(template);
+            content.push_str("\n\n");
+            any_added = true;
+        }
+
+        let secrets = secrets_section(&content, &self.secrets);
+        if !any_added && secrets.is_empty() && self.custom.is_empty() {
+            return Err(Error::NoTemplates);
+        }
+        content.push_str(&secrets);
+
+        if !self.custom.is_empty() {
+            content.push_str("### Custom ###\n");
+            for pattern in &self.custom {
+                content.push_str(pattern);
+                content.push('\n');
+            }
+            content.push('\n');
+        }
+
+        Ok(finish_for_kind(content, self.kind))
+    }
+}
+
+/// Matcher for the patterns in generated content, rooted at the file's directory.
+pub fn compile(content: &str) -> Gitignore {
+    let mut builder = GitignoreBuilder::new("");
+    for line in content.lines() {
+        // Template lines that fail to parse are ignored by git as well
+        let _ = builder.add_line(None, line);
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Patterns for secrets that `content` does not already ignore, as a section to append.
+///
+/// Returns an empty string when every secret is covered.
+fn secrets_section(content: &str, secrets: &[String]) -> String {
+    let existing = compile(content);
+
+    let mut patterns: Vec<&str> = Vec::new();
+    for secret in secrets {
+        if existing.matched_path_or_any_parents(secret, false).is_ignore() {
+            continue;
+        }
+        let name = secret.rsplit('/').next().unwrap_or(secret);
+        if let Some(pattern) = analyzer::secret_pattern(name) {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+    }
+
+    if patterns.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("### Secrets ###\n");
+    section.push_str("# Local secrets and environment files found in this project\n");
+    for pattern in &patterns {
+        section.push_str(pattern);
+        section.push('\n');
+    }
+    // Keep committed env templates visible
+    if patterns.contains(&".env.*") {
+        for exception in analyzer::SECRET_EXCEPTIONS {
+            section.push_str(&format!("!{}\n", exception));
+        }
+    }
+    section.push('\n');
+    section
+}
+
+/// Append the kind-specific section and adapt patterns to the target tool.
+fn finish_for_kind(mut content: String, kind: IgnoreKind) -> String {
+    let (title, patterns) = kind.extras();
+    if !patterns.is_empty() {
+        content.push_str(&format!("### {} ###\n", title));
+        for pattern in patterns {
+            content.push_str(pattern);
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+
+    let rewrite: fn(&str) -> String = match kind {
+        IgnoreKind::Dockerignore => to_dockerignore,
+        IgnoreKind::Helmignore => to_helmignore,
+        _ => return content,
+    };
+    content.lines().map(|line| rewrite(line) + "\n").collect()
+}
+
+/// Rewrite a gitignore line for Docker, which anchors every pattern at the
+/// context root: unanchored patterns need a `**/` prefix to match at any depth.
+fn to_dockerignore(line: &str) -> String {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return line.to_string();
+    }
+    let (negate, pattern) = match trimmed.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", trimmed),
+    };
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.trim_end_matches('/').contains('/') || pattern.starts_with("**") => {
+            pattern.to_string()
+        }
+        None => format!("**/{}", pattern),
+    };
+    format!("{}{}", negate, pattern)
+}
+
+/// Rewrite a gitignore line for Helm, which has no `**`: a leading `**/` is
+/// implied for patterns without a slash, and `dir/**` becomes the directory
+/// itself. Other `**` patterns cannot be expressed and are commented out.
+fn to_helmignore(line: &str) -> String {
+    let trimmed = line.trim_end();
+    if !trimmed.contains("**") || trimmed.starts_with('#') {
+        return line.to_string();
+    }
+    let (negate, pattern) = match trimmed.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", trimmed),
+    };
+
+    let mut rest = pattern;
+    while let Some(inner) = rest.strip_prefix("**/") {
+        rest = inner;
+    }
+    let rewritten = if !rest.contains("**") && !rest.trim_end_matches('/').contains('/') {
+        Some(rest.to_string())
+    } else {
+        pattern
+            .strip_suffix("/**")
+            .map(|dir| dir.trim_start_matches('/'))
+            .filter(|dir| !dir.is_empty() && !dir.contains("**"))
+            .map(|dir| format!("/{}/", dir))
+    };
+    match rewritten {
+        Some(pattern) => format!("{}{}", negate, pattern),
+        None => format!("# Not supported by Helm: {}", trimmed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_secrets_section_adds_uncovered() {
+        let section = secrets_section("*.log\n", &strings(&[".env", "certs/server.pem"]));
+        assert!(section.starts_with("### Secrets ###"));
+        assert!(section.contains("\n.env\n"));
+        assert!(section.contains("\n*.pem\n"));
+    }
+
+    #[test]
+    fn test_secrets_section_skips_covered() {
+        let section = secrets_section(".env\n*.pem\n", &strings(&[".env", "certs/server.pem"]));
+        assert!(section.is_empty());
+    }
+
+    #[test]
+    fn test_secrets_section_keeps_env_templates() {
+        let section = secrets_section("", &strings(&[".env.local"]));
+        assert!(section.contains(".env.*\n"));
+        assert!(section.contains("!.env.example\n"));
+    }
+
+    #[test]
+    fn test_finish_for_dockerignore() {
+        let content = finish_for_kind(
+            "# Logs\n*.log\n/build\n!keep.log\ndocs/api/\n".into(),
+            IgnoreKind::Dockerignore,
+        );
+        assert!(content.starts_with("# Logs\n**/*.log\nbuild\n!**/keep.log\ndocs/api/\n"));
+        assert!(content.contains("### Docker ###\n**/.git\n"));
+        assert!(content.contains("\n**/Dockerfile*\n"));
+    }
+
+    #[test]
+    fn test_finish_for_helmignore() {
+        let content = finish_for_kind(
+            "**/*.rs.bk\n**/node_modules/\ndocs/**\n!**/keep.txt\na/**/b\n*.log\n".into(),
+            IgnoreKind::Helmignore,
+        );
+        assert!(content.starts_with(
+            "*.rs.bk\nnode_modules/\n/docs/\n!keep.txt\n# Not supported by Helm: a/**/b\n*.log\n"
+        ));
+        assert!(content.contains("### Helm ###\n.git/\n"));
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let unknown = Generator::new().technology("no-such-template").build();
+        assert_eq!(unknown, Err(Error::UnknownTemplate("no-such-template".to_string())));
+
+        let empty = Generator::new().technology("no-such-template").allow_missing(true).build();
+        assert_eq!(empty, Err(Error::NoTemplates));
+
+        let secrets = Generator::new().secrets([".env"]).build().unwrap();
+        assert!(secrets.contains("### Secrets ###\n"));
+    }
+}
@@ -0,0 +1,13 @@
+//! Generate `.gitignore` and related ignore files from project analysis.
+//!
+//! The `gitignore-gen` binary is a thin CLI over this crate:
+//!
+//! - [`analyzer`] walks a project and detects technologies, secrets and artefacts
+//! - [`templates`] is the registry of embedded [github/gitignore] templates
+//! - [`generator`] combines templates into an ignore file
+//!
+//! [github/gitignore]: https://github.com/github/gitignore
+
+pub mod analyzer;
+pub mod generator;
+pub mod templates;
@@ -1,8 +1,9 @@
-mod analyzer;
 mod commands;
-mod templates;
+mod config;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use gitignore_gen::generator::IgnoreKind;
+use gitignore_gen::templates;
 use std::process;
 
 #[derive(Parser)]
@@ -16,6 +17,10 @@ appropriate .gitignore files by detecting technologies and frameworks.
 Examples:
   gitignore-gen              # Auto-detect and generate
   gitignore-gen rust node    # Generate for specific technologies
+  gitignore-gen --recursive  # One .gitignore per project root (monorepos)
+  gitignore-gen --depth 6 --exclude third_party
+  gitignore-gen --kind dockerignore
+  gitignore-gen gitattributes --dry-run
   gitignore-gen --list       # Show available templates")]
 struct Cli {
     #[command(subcommand)]
@@ -29,6 +34,37 @@ struct Cli {
     #[arg(short, long)]
     force: bool,
 
+    /// Print the generated file instead of writing it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Generate a .gitignore for every project root (monorepo mode)
+    #[arg(short, long, conflicts_with = "technologies")]
+    recursive: bool,
+
+    #[command(flatten)]
+    scan: commands::ScanOptions,
+
+    /// Also add templates for the editor set in $VISUAL/$EDITOR or hosting the terminal
+    #[arg(long)]
+    editor_env: bool,
+
+    /// Add macOS/Linux/Windows templates (current platform, or a comma-separated list)
+    #[arg(long, value_name = "OS", num_args = 0..=1, value_delimiter = ',')]
+    os: Option<Vec<String>>,
+
+    /// Add suggested patterns for large binaries, archives and generated files
+    #[arg(long)]
+    accept_suggestions: bool,
+
+    /// Kind of ignore file to generate
+    #[arg(long, value_enum, default_value_t = Kind::Gitignore)]
+    kind: Kind,
+
+    /// Never access the network; commands that need it fail immediately
+    #[arg(long, global = true)]
+    offline: bool,
+
     /// List available templates
     #[arg(short, long)]
     list: bool,
@@ -38,17 +74,87 @@ struct Cli {
     version: (),
 }
 
+/// Command-line names for [`IgnoreKind`], kept out of the library so it does not depend on clap.
+#[derive(Clone, Copy, ValueEnum)]
+enum Kind {
+    Gitignore,
+    Dockerignore,
+    Npmignore,
+    Prettierignore,
+    Eslintignore,
+    Helmignore,
+    Gcloudignore,
+}
+
+impl From<Kind> for IgnoreKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Gitignore => IgnoreKind::Gitignore,
+            Kind::Dockerignore => IgnoreKind::Dockerignore,
+            Kind::Npmignore => IgnoreKind::Npmignore,
+            Kind::Prettierignore => IgnoreKind::Prettierignore,
+            Kind::Eslintignore => IgnoreKind::Eslintignore,
+            Kind::Helmignore => IgnoreKind::Helmignore,
+            Kind::Gcloudignore => IgnoreKind::Gcloudignore,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
+    /// Generate a .gitattributes file (LFS, linguist-generated, line endings)
+    Gitattributes {
+        /// Technologies to include (auto-detect when omitted)
+        #[arg(value_name = "TECH")]
+        technologies: Vec<String>,
+
+        /// Force overwrite existing .gitattributes file
+        #[arg(short, long)]
+        force: bool,
+
+        /// Print the generated file instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        scan: commands::ScanOptions,
+    },
     /// Uninstall gitignore-gen (removes the binary)
     Uninstall,
     /// Check for updates
-    Update,
+    Update {
+        /// Install the latest release even if it is older than this version
+        #[arg(long, conflicts_with = "check")]
+        allow_downgrade: bool,
+
+        /// Only report whether an update is available (exit code 10 if so)
+        #[arg(long)]
+        check: bool,
+
+        /// Print the --check result as JSON
+        #[arg(long, requires = "check")]
+        json: bool,
+
+        /// Install a specific release (e.g. v2.0.1) instead of the latest
+        #[arg(long, value_name = "VERSION", conflicts_with = "check")]
+        version: Option<String>,
+
+        /// Restore the binary replaced by the last update
+        #[arg(long, conflicts_with_all = ["check", "version", "allow_downgrade"])]
+        rollback: bool,
+    },
+    /// Refresh the cached latest release (run in the background by generate)
+    #[command(name = commands::REFRESH_COMMAND, hide = true)]
+    RefreshUpdateCache,
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    if cli.offline {
+        commands::set_offline();
+    }
+
     // Handle --list flag
     if cli.list {
         println!("Available templates:");
@@ -59,9 +165,38 @@ fn main() {
     }
 
     let result = match &cli.command {
+        Some(Commands::Gitattributes { technologies, force, dry_run, scan }) => {
+            commands::gitattributes(technologies, *force, *dry_run, scan)
+        }
         Some(Commands::Uninstall) => commands::uninstall(),
-        Some(Commands::Update) => commands::update(),
-        None => commands::generate(cli.force, &cli.technologies),
+        Some(Commands::RefreshUpdateCache) => {
+            commands::refresh_update_cache();
+            Ok(())
+        }
+        Some(Commands::Update { check: true, json, .. }) => match commands::check(*json) {
+            Ok(0) => Ok(()),
+            Ok(code) => process::exit(code),
+            Err(e) => Err(e),
+        },
+        Some(Commands::Update { allow_downgrade, version, rollback, .. }) => {
+            commands::update(&commands::UpdateOptions {
+                allow_downgrade: *allow_downgrade,
+                version: version.clone(),
+                rollback: *rollback,
+            })
+        }
+        None => commands::generate(&commands::GenerateOptions {
+            force: cli.force,
+            dry_run: cli.dry_run,
+            technologies: cli.technologies.clone(),
+            recursive: cli.recursive,
+            scan: cli.scan.clone(),
+            editor_env: cli.editor_env,
+            os: cli.os.clone(),
+            accept_suggestions: cli.accept_suggestions,
+            kind: cli.kind.into(),
+        })
+        .map(|()| commands::notify_if_outdated()),
     };
 
     if let Err(e) = result {
@@ -69,3 +204,19 @@ fn main() {
         process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_os_values() {
+        let os = |args: &[&str]| Cli::try_parse_from(args).unwrap().os;
+        let names = |list: &[&str]| Some(list.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+
+        assert_eq!(os(&["gitignore-gen", "rust"]), None);
+        assert_eq!(os(&["gitignore-gen", "rust", "--os"]), names(&[]));
+        assert_eq!(os(&["gitignore-gen", "--os", "macos", "rust"]), names(&["macos"]));
+        assert_eq!(os(&["gitignore-gen", "--os=macos,windows"]), names(&["macos", "windows"]));
+    }
+}